@@ -0,0 +1,491 @@
+//! A blocking client for code which is not async.
+//!
+//! `PorkbunnClient` here has the same methods as the async
+//! `porkbunn_client::PorkbunnClient` and returns the same models. It drives the
+//! async client on a single-threaded runtime it owns, so it must not be used
+//! from within an async context, where blocking would stall the executor.
+//!
+//! ```rust, no_run
+//! use porkbun_rs::blocking::PorkbunnClient;
+//!
+//! # fn run() -> Result<(), porkbun_rs::errors::PorkbunnError> {
+//! let client = PorkbunnClient::builder()
+//!     .credentials("pk1_...", "sk1_...")
+//!     .build()?;
+//! for record in client.list_dns_records("example.com")?.records {
+//!     println!("{} {} {}", record.name, record.type_field, record.content);
+//! }
+//! for domain in client.stream_domains(false) {
+//!     println!("{}", domain?.domain);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::client::{Endpoint, RetryPolicy};
+use crate::dns_record::DnsRecord;
+use crate::errors::PorkbunnError;
+use crate::porkbunn_client::{
+    self, Domain, DsRecord, Record, RecordUpdate, ResponseAddUrlForward, ResponseApiKeyRequest,
+    ResponseApiKeyRetrieve, ResponseCheckDomain, ResponseCreateDsRecord, ResponseCreateGlue,
+    ResponseCreateRecord, ResponseDeleteDsRecord, ResponseDeleteGlue, ResponseDeleteRecord,
+    ResponseDeleteUrlForward, ResponseEditRecord, ResponseGetDsRecords, ResponseGetGlue,
+    ResponseGetNameservers, ResponseListDnsRecords, ResponseListDomains, ResponseListUrlForwards,
+    ResponsePing, ResponsePricing, ResponseRegisterDomain, ResponseSslBundle,
+    ResponseUpdateAutoRenew, ResponseUpdateGlue, ResponseUpdateNameservers, UrlForward,
+};
+use crate::rate_limit::{EndpointClass, RateLimit};
+use secrecy::SecretString;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Builds a blocking `PorkbunnClient`, with the same options as
+/// `porkbunn_client::PorkbunnClientBuilder`.
+#[derive(Debug, Default)]
+pub struct PorkbunnClientBuilder {
+    inner: porkbunn_client::PorkbunnClientBuilder,
+}
+
+impl PorkbunnClientBuilder {
+    /// Sets the base URL of the API, defaults to `DEFAULT_BASE_URL`.
+    pub fn base_url<S: Into<String>>(self, base_url: S) -> Self {
+        self.inner.base_url(base_url).into()
+    }
+
+    /// Sets the version of the API, defaults to `DEFAULT_VERSION`.
+    pub fn version<S: Into<String>>(self, version: S) -> Self {
+        self.inner.version(version).into()
+    }
+
+    /// Sets the API key and secret used to authenticate requests.
+    pub fn credentials<S: Into<SecretString>, T: Into<SecretString>>(
+        self,
+        api_key: S,
+        api_secret: T,
+    ) -> Self {
+        self.inner.credentials(api_key, api_secret).into()
+    }
+
+    /// Sets the timeout for a whole request, from connecting until the response body has been read.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.inner.timeout(timeout).into()
+    }
+
+    /// Sets the timeout for establishing a connection.
+    pub fn connect_timeout(self, timeout: Duration) -> Self {
+        self.inner.connect_timeout(timeout).into()
+    }
+
+    /// Sends all requests through a proxy, e.g. `http://proxy:3128` or `socks5://127.0.0.1:1080`.
+    pub fn proxy<S: Into<String>>(self, proxy_url: S) -> Self {
+        self.inner.proxy(proxy_url).into()
+    }
+
+    /// Sets the `User-Agent` header, defaults to `porkbun-rs/<version>`.
+    pub fn user_agent<S: Into<String>>(self, user_agent: S) -> Self {
+        self.inner.user_agent(user_agent).into()
+    }
+
+    /// Trusts the PEM encoded certificates in `pem` in addition to the system roots.
+    pub fn add_root_certificate_pem<B: Into<Vec<u8>>>(self, pem: B) -> Self {
+        self.inner.add_root_certificate_pem(pem).into()
+    }
+
+    /// Uses a caller-supplied HTTP client as is. It cannot be combined with the
+    /// other HTTP options of this builder.
+    pub fn http_client(self, client: reqwest::Client) -> Self {
+        self.inner.http_client(client).into()
+    }
+
+    /// Sets how transient failures are retried, defaults to `RetryPolicy::default()`.
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        self.inner.retry_policy(retry_policy).into()
+    }
+
+    /// Throttles requests to endpoints of `class` on the client side.
+    pub fn rate_limit(self, class: EndpointClass, limit: RateLimit) -> Self {
+        self.inner.rate_limit(class, limit).into()
+    }
+
+    /// Builds the blocking `PorkbunnClient`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the client, or an error of type `PorkbunnError` if the
+    /// async client cannot be built or the runtime cannot be started.
+    pub fn build(self) -> Result<PorkbunnClient, PorkbunnError> {
+        self.inner.build().and_then(PorkbunnClient::from_async)
+    }
+}
+
+impl From<porkbunn_client::PorkbunnClientBuilder> for PorkbunnClientBuilder {
+    fn from(inner: porkbunn_client::PorkbunnClientBuilder) -> Self {
+        PorkbunnClientBuilder { inner }
+    }
+}
+
+/// A blocking client for the Porkbun API.
+///
+/// Cloning the client is cheap; clones share the runtime, connection pool and
+/// rate limits.
+#[derive(Debug, Clone)]
+pub struct PorkbunnClient {
+    inner: porkbunn_client::PorkbunnClient,
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+impl PorkbunnClient {
+    /// Creates a new blocking client with a default HTTP client.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the Porkbun API.
+    /// * `version` - The version of the Porkbun API.
+    /// * `api_key` - The API key for authentication.
+    /// * `api_secret` - The API secret for authentication.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new `PorkbunnClient`, or an error of type `PorkbunnError`
+    /// if the base URL is invalid or the runtime cannot be started.
+    pub fn new(
+        base_url: &str,
+        version: &str,
+        api_key: &str,
+        api_secret: &str,
+    ) -> Result<PorkbunnClient, PorkbunnError> {
+        porkbunn_client::PorkbunnClient::new(base_url, version, api_key, api_secret)
+            .and_then(PorkbunnClient::from_async)
+    }
+
+    /// Returns a builder to configure the client.
+    pub fn builder() -> PorkbunnClientBuilder {
+        PorkbunnClientBuilder::default()
+    }
+
+    /// Wraps an async client, starting the runtime to drive it.
+    pub fn from_async(
+        client: porkbunn_client::PorkbunnClient,
+    ) -> Result<PorkbunnClient, PorkbunnError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(PorkbunnClient {
+            inner: client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::execute`.
+    pub fn execute<E: Endpoint>(&self, endpoint: &E) -> Result<E::Response, PorkbunnError> {
+        self.runtime.block_on(self.inner.execute(endpoint))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::request_api_key`.
+    pub fn request_api_key(&self) -> Result<ResponseApiKeyRequest, PorkbunnError> {
+        self.runtime.block_on(self.inner.request_api_key())
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::retrieve_api_key`.
+    pub fn retrieve_api_key(
+        &self,
+        request_token: &str,
+    ) -> Result<ResponseApiKeyRetrieve, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.retrieve_api_key(request_token))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::ping`.
+    pub fn ping(&self) -> Result<ResponsePing, PorkbunnError> {
+        self.runtime.block_on(self.inner.ping())
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::get_pricing`.
+    pub fn get_pricing(&self) -> Result<ResponsePricing, PorkbunnError> {
+        self.runtime.block_on(self.inner.get_pricing())
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::list_dns_records`.
+    pub fn list_dns_records(&self, name: &str) -> Result<ResponseListDnsRecords, PorkbunnError> {
+        self.runtime.block_on(self.inner.list_dns_records(name))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::get_dns_record`.
+    pub fn get_dns_record(&self, domain: &str, id: u64) -> Result<Record, PorkbunnError> {
+        self.runtime.block_on(self.inner.get_dns_record(domain, id))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::create_dns_record`.
+    pub fn create_dns_record(
+        &self,
+        domain: &str,
+        name: &str,
+        record: &DnsRecord,
+        ttl: u32,
+    ) -> Result<ResponseCreateRecord, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.create_dns_record(domain, name, record, ttl))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::edit_dns_record`.
+    pub fn edit_dns_record(
+        &self,
+        domain: &str,
+        id: u64,
+        update: &RecordUpdate,
+    ) -> Result<ResponseEditRecord, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.edit_dns_record(domain, id, update))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::edit_dns_records_by_name_type`.
+    pub fn edit_dns_records_by_name_type(
+        &self,
+        domain: &str,
+        update: &RecordUpdate,
+    ) -> Result<ResponseEditRecord, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.edit_dns_records_by_name_type(domain, update))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::retrieve_dns_records_by_name_type`.
+    pub fn retrieve_dns_records_by_name_type(
+        &self,
+        domain: &str,
+        record_type: &str,
+        name: &str,
+    ) -> Result<ResponseListDnsRecords, PorkbunnError> {
+        self.runtime
+            .block_on(
+                self.inner
+                    .retrieve_dns_records_by_name_type(domain, record_type, name),
+            )
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::delete_dns_records_by_name_type`.
+    pub fn delete_dns_records_by_name_type(
+        &self,
+        domain: &str,
+        record_type: &str,
+        name: &str,
+    ) -> Result<ResponseDeleteRecord, PorkbunnError> {
+        self.runtime.block_on(
+            self.inner
+                .delete_dns_records_by_name_type(domain, record_type, name),
+        )
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::delete_dns_record`.
+    pub fn delete_dns_record(
+        &self,
+        domain: &str,
+        id: u64,
+    ) -> Result<ResponseDeleteRecord, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.delete_dns_record(domain, id))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::get_nameservers`.
+    pub fn get_nameservers(&self, domain: &str) -> Result<ResponseGetNameservers, PorkbunnError> {
+        self.runtime.block_on(self.inner.get_nameservers(domain))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::update_nameservers`.
+    pub fn update_nameservers(
+        &self,
+        domain: &str,
+        nameservers: &[String],
+    ) -> Result<ResponseUpdateNameservers, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.update_nameservers(domain, nameservers))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::add_url_forward`.
+    pub fn add_url_forward(
+        &self,
+        domain: &str,
+        forward: &UrlForward,
+    ) -> Result<ResponseAddUrlForward, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.add_url_forward(domain, forward))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::list_url_forwards`.
+    pub fn list_url_forwards(
+        &self,
+        domain: &str,
+    ) -> Result<ResponseListUrlForwards, PorkbunnError> {
+        self.runtime.block_on(self.inner.list_url_forwards(domain))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::delete_url_forward`.
+    pub fn delete_url_forward(
+        &self,
+        domain: &str,
+        id: u64,
+    ) -> Result<ResponseDeleteUrlForward, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.delete_url_forward(domain, id))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::create_glue`.
+    pub fn create_glue(
+        &self,
+        domain: &str,
+        subdomain: &str,
+        ips: &[IpAddr],
+    ) -> Result<ResponseCreateGlue, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.create_glue(domain, subdomain, ips))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::update_glue`.
+    pub fn update_glue(
+        &self,
+        domain: &str,
+        subdomain: &str,
+        ips: &[IpAddr],
+    ) -> Result<ResponseUpdateGlue, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.update_glue(domain, subdomain, ips))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::delete_glue`.
+    pub fn delete_glue(
+        &self,
+        domain: &str,
+        subdomain: &str,
+    ) -> Result<ResponseDeleteGlue, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.delete_glue(domain, subdomain))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::get_glue`.
+    pub fn get_glue(&self, domain: &str) -> Result<ResponseGetGlue, PorkbunnError> {
+        self.runtime.block_on(self.inner.get_glue(domain))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::create_ds_record`.
+    pub fn create_ds_record(
+        &self,
+        domain: &str,
+        record: &DsRecord,
+    ) -> Result<ResponseCreateDsRecord, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.create_ds_record(domain, record))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::get_ds_records`.
+    pub fn get_ds_records(&self, domain: &str) -> Result<ResponseGetDsRecords, PorkbunnError> {
+        self.runtime.block_on(self.inner.get_ds_records(domain))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::delete_ds_record`.
+    pub fn delete_ds_record(
+        &self,
+        domain: &str,
+        key_tag: u16,
+    ) -> Result<ResponseDeleteDsRecord, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.delete_ds_record(domain, key_tag))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::retrieve_ssl_bundle`.
+    pub fn retrieve_ssl_bundle(&self, domain: &str) -> Result<ResponseSslBundle, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.retrieve_ssl_bundle(domain))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::check_domain`.
+    pub fn check_domain(&self, domain: &str) -> Result<ResponseCheckDomain, PorkbunnError> {
+        self.runtime.block_on(self.inner.check_domain(domain))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::register_domain`.
+    pub fn register_domain(
+        &self,
+        domain: &str,
+        cost: u64,
+    ) -> Result<ResponseRegisterDomain, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.register_domain(domain, cost))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::update_auto_renew`.
+    pub fn update_auto_renew(
+        &self,
+        domains: &[String],
+        enabled: bool,
+    ) -> Result<ResponseUpdateAutoRenew, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.update_auto_renew(domains, enabled))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::list_domains_page`.
+    pub fn list_domains_page(
+        &self,
+        start: u64,
+        include_labels: bool,
+    ) -> Result<ResponseListDomains, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.list_domains_page(start, include_labels))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::list_domains`.
+    pub fn list_domains(&self, include_labels: bool) -> Result<ResponseListDomains, PorkbunnError> {
+        self.runtime
+            .block_on(self.inner.list_domains(include_labels))
+    }
+
+    /// Blocking version of `porkbunn_client::PorkbunnClient::stream_domains`.
+    ///
+    /// The next page is fetched only once the previous one has been consumed.
+    /// The iterator ends after the first error.
+    pub fn stream_domains(&self, include_labels: bool) -> Domains<'_> {
+        Domains {
+            client: self,
+            include_labels,
+            next_start: Some(0),
+            page: Vec::new().into_iter(),
+        }
+    }
+}
+
+/// Iterator over all domains of an account, returned by
+/// `PorkbunnClient::stream_domains`.
+#[derive(Debug)]
+pub struct Domains<'a> {
+    client: &'a PorkbunnClient,
+    include_labels: bool,
+    /// Offset of the next page, `None` once the last page or an error was seen.
+    next_start: Option<u64>,
+    page: std::vec::IntoIter<Domain>,
+}
+
+impl Iterator for Domains<'_> {
+    type Item = Result<Domain, PorkbunnError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(domain) = self.page.next() {
+                return Some(Ok(domain));
+            }
+            let start = self.next_start?;
+            match self.client.list_domains_page(start, self.include_labels) {
+                Ok(response) => {
+                    self.next_start = if response.domains.is_empty() {
+                        None
+                    } else {
+                        Some(start + response.domains.len() as u64)
+                    };
+                    self.page = response.domains.into_iter();
+                }
+                Err(err) => {
+                    self.next_start = None;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
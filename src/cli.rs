@@ -1,7 +1,16 @@
-use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 
-use crate::{porkbunn_client, serde_ext::SerdeExt};
+use crate::dns_record::{DnsRecord, RecordType};
+use crate::errors::PorkbunnError;
+use crate::porkbunn_client::{self, ForwardType};
+use crate::provider::{fqdn, subdomain_of};
+use crate::serde_ext::SerdeExt;
 use clap_complete::{Generator, Shell, generate};
+use serde::Serialize;
+use std::io::Write;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -38,41 +47,85 @@ struct Cli {
 }
 
 #[derive(Debug, PartialEq, ValueEnum, Clone)]
-enum RecordType {
-    A,
-    Mx,
-    Cname,
-    Alias,
-    Txt,
-    Ns,
-    Aaaa,
-    Srv,
-    Tlsa,
-    Caa,
-    Https,
-    Svcb,
+enum PricingSort {
+    Tld,
+    Registration,
+    Renewal,
+    Transfer,
 }
 
-impl std::fmt::Display for RecordType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let v = match self {
-            RecordType::A => "A".to_string(),
-            RecordType::Mx => "MX".to_string(),
-            RecordType::Cname => "CNAME".to_string(),
-            RecordType::Alias => "ALIAS".to_string(),
-            RecordType::Txt => "TXT".to_string(),
-            RecordType::Ns => "NS".to_string(),
-            RecordType::Aaaa => "AAAA".to_string(),
-            RecordType::Srv => "SRV".to_string(),
-            RecordType::Tlsa => "TLSA".to_string(),
-            RecordType::Caa => "CAA".to_string(),
-            RecordType::Https => "HTTPS".to_string(),
-            RecordType::Svcb => "SVCB".to_string(),
-        };
-        write!(f, "{}", v)
+#[derive(Debug, PartialEq, ValueEnum, Clone, Copy)]
+enum Toggle {
+    On,
+    Off,
+}
+
+/// Selects domains of the account by their labels, TLD or expiry.
+#[derive(Args, Debug, Default)]
+struct DomainFilter {
+    /// Only domains with this label
+    #[arg(short, long, value_name = "LABEL")]
+    label: Option<String>,
+
+    /// Only domains under this TLD, e.g. `com`
+    #[arg(short, long, value_name = "TLD")]
+    tld: Option<String>,
+
+    /// Only domains expiring within this many days
+    #[arg(short, long, value_name = "DAYS")]
+    expiring_within: Option<i64>,
+}
+
+impl DomainFilter {
+    fn is_empty(&self) -> bool {
+        self.label.is_none() && self.tld.is_none() && self.expiring_within.is_none()
+    }
+
+    fn matches(&self, domain: &porkbunn_client::Domain) -> bool {
+        if let Some(label) = &self.label
+            && !domain
+                .labels
+                .iter()
+                .any(|l| l.title.eq_ignore_ascii_case(label))
+        {
+            return false;
+        }
+        if let Some(tld) = &self.tld
+            && !domain.tld.eq_ignore_ascii_case(tld.trim_start_matches('.'))
+        {
+            return false;
+        }
+        if let Some(days) = self.expiring_within {
+            let deadline = chrono::Utc::now().naive_utc() + chrono::Duration::days(days);
+            return domain
+                .expires_at()
+                .is_some_and(|expires_at| expires_at <= deadline);
+        }
+        true
     }
 }
 
+/// A single line of the `check` output.
+#[derive(Serialize)]
+struct CheckRow<'a> {
+    domain: &'a str,
+    #[serde(flatten)]
+    availability: &'a porkbunn_client::DomainAvailability,
+}
+
+/// Pause between domain checks when the API does not report its limits.
+const DEFAULT_CHECK_PAUSE: Duration = Duration::from_secs(10);
+
+/// A single line of the `pricing` output.
+#[derive(Serialize)]
+struct PricingRow<'a> {
+    tld: &'a str,
+    #[serde(flatten)]
+    pricing: &'a porkbunn_client::TldPricing,
+    /// Renewal is considerably more expensive than registration.
+    high_renewal: bool,
+}
+
 fn print_completions<G: Generator>(gene: G, cmd: &mut clap::Command) {
     generate(
         gene,
@@ -102,12 +155,16 @@ enum Commands {
         #[arg(short, long, value_name = "DOMAIN")]
         domain: String,
 
-        /// IP address for the DNS record
-        #[arg(short, long, value_name = "IP_ADDRESS")]
+        /// Content of the DNS record, e.g. an IP address or `weight port target` for SRV records
+        #[arg(short, long, value_name = "CONTENT", visible_alias = "content")]
         ip_address: String,
 
-        /// Delete the record if it already exists
-        #[arg(short, long, value_name = "DELETE_EXISTING")]
+        /// Priority, required for MX and SRV records
+        #[arg(short, long, value_name = "PRIO")]
+        prio: Option<u16>,
+
+        /// Replace existing records with the same name and type
+        #[arg(long)]
         delete_existing: bool,
     },
 
@@ -118,16 +175,175 @@ enum Commands {
         domain: String,
 
         /// ID of the record
-        #[arg(short, long, value_name = "ID")]
-        id: u64,
+        #[arg(
+            short,
+            long,
+            value_name = "ID",
+            required_unless_present = "record_type",
+            conflicts_with = "record_type"
+        )]
+        id: Option<u64>,
+
+        /// Subdomain of the records, used together with `--type`. Omit for the root domain
+        #[arg(short, long, value_name = "NAME", requires = "record_type")]
+        name: Option<String>,
+
+        /// Record type, deletes all records of this type instead of a single `--id`
+        #[arg(short = 'r', long = "type", value_name = "RECORD_TYPE", value_enum)]
+        record_type: Option<RecordType>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        skip_confirm: bool,
+    },
+
+    /// Edit existing DNS records in place, keeping their IDs
+    EditRecord {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// ID of the record
+        #[arg(
+            short,
+            long,
+            value_name = "ID",
+            required_unless_present = "record_type",
+            conflicts_with = "record_type"
+        )]
+        id: Option<u64>,
+
+        /// Subdomain of the records, used together with `--type`. Omit for the root domain
+        #[arg(short, long, value_name = "NAME", requires = "record_type")]
+        name: Option<String>,
+
+        /// Record type, edits all records of this type instead of a single `--id`
+        #[arg(short = 'r', long = "type", value_name = "RECORD_TYPE", value_enum)]
+        record_type: Option<RecordType>,
+
+        /// New content for the DNS record, e.g. an IP address
+        #[arg(short, long, value_name = "CONTENT")]
+        content: Option<String>,
+
+        /// New time to live
+        #[arg(short, long, value_name = "TTL")]
+        ttl: Option<u32>,
+
+        /// New priority, for record types which support it
+        #[arg(short, long, value_name = "PRIO")]
+        prio: Option<u16>,
+
+        /// New notes, an empty string clears them
+        #[arg(long, value_name = "NOTES")]
+        notes: Option<String>,
+    },
+
+    /// Show the nameservers of a domain
+    GetNs {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+    },
+
+    /// Replace the nameservers of a domain
+    SetNs {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Nameserver, e.g. `ns1.example.net`. Pass once per nameserver
+        #[arg(short, long = "ns", value_name = "NAMESERVER", required = true)]
+        nameservers: Vec<String>,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        skip_confirm: bool,
+    },
+
+    /// Turn auto-renewal of domains on or off
+    AutoRenew {
+        /// New auto-renewal state
+        #[arg(value_enum)]
+        state: Toggle,
+
+        /// Domain to update. Pass once per domain
+        #[arg(short, long = "domain", value_name = "DOMAIN", conflicts_with_all = ["all", "label", "tld", "expiring_within"])]
+        domains: Vec<String>,
+
+        /// Update all domains of the account matching the filters
+        #[arg(long)]
+        all: bool,
+
+        #[command(flatten)]
+        filter: DomainFilter,
+    },
+
+    /// Check whether domains are available and quote their prices
+    Check {
+        /// Domains to check. Read from stdin, one per line, if none are given
+        #[arg(value_name = "DOMAIN")]
+        domains: Vec<String>,
+    },
+
+    /// Manage DNSSEC DS records of a domain at the registry
+    Dnssec {
+        #[command(subcommand)]
+        command: DnssecCommands,
+    },
+
+    /// Manage URL forwarding of a domain
+    Forward {
+        #[command(subcommand)]
+        command: ForwardCommands,
+    },
+
+    /// Manage glue records for nameservers under a domain
+    Glue {
+        #[command(subcommand)]
+        command: GlueCommands,
+    },
+
+    /// Register a new domain, paid from the account balance
+    Register {
+        /// Domain to register, e.g. example.com
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Refuse premium domains and domains costing more than this many USD
+        #[arg(short, long, value_name = "USD")]
+        max_price: Option<f64>,
+
+        /// Nameserver to set after registration. Pass once per nameserver
+        #[arg(short, long = "ns", value_name = "NAMESERVER")]
+        nameservers: Vec<String>,
 
         /// Skip confirmation prompt
         #[arg(short, long)]
         skip_confirm: bool,
     },
 
+    /// Manage the SSL certificates Porkbun issues for a domain
+    Ssl {
+        #[command(subcommand)]
+        command: SslCommands,
+    },
+
+    /// Get a single DNS record by its ID
+    GetRecord {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// ID of the record
+        #[arg(short, long, value_name = "ID")]
+        id: u64,
+    },
+
     /// List all domains associated with the account
-    ListDomains,
+    ListDomains {
+        #[command(flatten)]
+        filter: DomainFilter,
+    },
 
     /// List all records for a given domain
     ListRecords {
@@ -135,6 +351,220 @@ enum Commands {
         #[arg(short, long, value_name = "DOMAIN")]
         domain: String,
     },
+
+    /// Provision a new API key and store it in the local credentials file
+    Login {
+        /// Seconds between checks whether the key has been approved
+        #[arg(short, long, value_name = "SECONDS", default_value_t = 5)]
+        poll_interval: u64,
+
+        /// Seconds to wait for the approval before giving up
+        #[arg(short, long, value_name = "SECONDS", default_value_t = 600)]
+        timeout: u64,
+    },
+
+    /// Show registration, renewal and transfer prices per TLD
+    Pricing {
+        /// Only show this TLD, e.g. `com`. Can be passed multiple times
+        #[arg(short, long = "tld", value_name = "TLD")]
+        tlds: Vec<String>,
+
+        /// Sort order of the output
+        #[arg(short, long, value_name = "SORT", value_enum, default_value_t = PricingSort::Tld)]
+        sort: PricingSort,
+
+        /// Flag TLDs whose renewal price is at least this many times the registration price
+        #[arg(short, long, value_name = "RATIO", default_value_t = 1.5)]
+        markup: f64,
+    },
+
+    /// Check the API credentials and print the public IP address seen by Porkbun
+    Ping,
+
+    /// Run a local fake of the Porkbun API and print its BASE_URL and credentials
+    #[cfg(feature = "testing")]
+    MockServer {
+        /// Address to listen on, use port 0 to pick a free port
+        #[arg(short, long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        listen: std::net::SocketAddr,
+
+        /// Domain to add to the fake account, may be repeated
+        #[arg(short, long = "domain", value_name = "DOMAIN")]
+        domains: Vec<String>,
+
+        /// Answer with HTTP 429 once more than this many requests arrive per minute
+        #[arg(short, long, value_name = "REQUESTS")]
+        rate_limit: Option<u32>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DnssecCommands {
+    /// Publish a DS record
+    Create {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Key tag of the DNSKEY
+        #[arg(short, long, value_name = "KEY_TAG")]
+        key_tag: u16,
+
+        /// IANA DNSSEC algorithm number, e.g. 13 for ECDSAP256SHA256
+        #[arg(short, long, value_name = "ALGORITHM")]
+        alg: u8,
+
+        /// IANA DS digest type, e.g. 2 for SHA-256
+        #[arg(short = 't', long, value_name = "DIGEST_TYPE")]
+        digest_type: u8,
+
+        /// Hex encoded digest of the DNSKEY
+        #[arg(short = 'g', long, value_name = "DIGEST")]
+        digest: String,
+    },
+
+    /// Print the published DS records in presentation format
+    List {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+    },
+
+    /// Delete a DS record
+    Delete {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Key tag of the DS record
+        #[arg(short, long, value_name = "KEY_TAG")]
+        key_tag: u16,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        skip_confirm: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ForwardCommands {
+    /// Forward a domain or subdomain to another URL
+    Add {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Subdomain to forward. Omit for the root domain
+        #[arg(short, long, value_name = "SUBDOMAIN")]
+        subdomain: Option<String>,
+
+        /// URL to forward to, e.g. `https://example.net`
+        #[arg(short, long, value_name = "LOCATION")]
+        location: String,
+
+        /// Redirect type
+        #[arg(short = 't', long = "type", value_name = "TYPE", value_enum, default_value_t = ForwardType::Temporary)]
+        forward_type: ForwardType,
+
+        /// Append the requested path to the location
+        #[arg(short, long)]
+        include_path: bool,
+
+        /// Also forward all subdomains
+        #[arg(short, long)]
+        wildcard: bool,
+    },
+
+    /// List all URL forwards of a domain
+    List {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+    },
+
+    /// Delete a URL forward
+    Delete {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// ID of the URL forward
+        #[arg(short, long, value_name = "ID")]
+        id: u64,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        skip_confirm: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum GlueCommands {
+    /// Create a glue record
+    Create {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Nameserver host without the domain, e.g. `ns1`
+        #[arg(short = 'n', long, value_name = "HOST")]
+        host: String,
+
+        /// IPv4 or IPv6 address of the nameserver. Pass once per address
+        #[arg(short, long = "ip", value_name = "IP", required = true)]
+        ips: Vec<IpAddr>,
+    },
+
+    /// Replace the addresses of a glue record
+    Update {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Nameserver host without the domain, e.g. `ns1`
+        #[arg(short = 'n', long, value_name = "HOST")]
+        host: String,
+
+        /// IPv4 or IPv6 address of the nameserver. Pass once per address
+        #[arg(short, long = "ip", value_name = "IP", required = true)]
+        ips: Vec<IpAddr>,
+    },
+
+    /// Delete a glue record
+    Delete {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Nameserver host without the domain, e.g. `ns1`
+        #[arg(short = 'n', long, value_name = "HOST")]
+        host: String,
+
+        /// Skip confirmation prompt
+        #[arg(short, long)]
+        skip_confirm: bool,
+    },
+
+    /// Show all glue records of a domain
+    Get {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum SslCommands {
+    /// Download the certificate bundle of a domain as PEM files
+    Fetch {
+        /// Domain
+        #[arg(short, long, value_name = "DOMAIN")]
+        domain: String,
+
+        /// Directory to write `fullchain.pem`, `privkey.pem` and `pubkey.pem` to
+        #[arg(short, long, value_name = "DIR", default_value = ".")]
+        output_dir: PathBuf,
+    },
 }
 
 /// Prompts the user for input and returns a boolean value based on the user's response.
@@ -157,15 +587,105 @@ fn ensure_input(msg: &str) -> bool {
     std::io::stdin().read_line(&mut input).unwrap();
     input = input.trim().to_string();
     if input.to_lowercase() == "y" {
-        return true;
+        true
     } else if input.to_lowercase() == "n" {
-        return false;
+        false
     } else {
         println!("Invalid input, please enter y or n");
         ensure_input(msg)
     }
 }
 
+/// Writes `content` to `path` unless the file already holds exactly this content.
+///
+/// The file is written to a temporary sibling first and then renamed, so readers
+/// never see a partially written file. With `private` set the file is only
+/// readable by its owner.
+///
+/// # Returns
+///
+/// `true` if the file was written, `false` if it was already up to date.
+fn write_if_changed(path: &Path, content: &str, private: bool) -> std::io::Result<bool> {
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        // an unchanged key may still be readable by others, e.g. from another tool
+        #[cfg(unix)]
+        if private {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        return Ok(false);
+    }
+
+    // a leftover temporary file would keep its old permissions, and `create_new`
+    // refuses to follow anything planted at the path after it has been removed
+    let tmp_path = path.with_extension("tmp");
+    let _ = std::fs::remove_file(&tmp_path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(if private { 0o600 } else { 0o644 });
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    std::fs::rename(&tmp_path, path)?;
+    Ok(true)
+}
+
+/// Returns the path of the credentials file written by `login`, e.g.
+/// `~/.config/porkbun-rs/credentials` on Linux.
+fn credentials_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("porkbun-rs").join("credentials"))
+}
+
+/// Reads whitespace separated domains from stdin, ignoring `#` comments.
+fn read_domains_from_stdin() -> std::io::Result<Vec<String>> {
+    let mut domains = Vec::new();
+    for line in std::io::stdin().lines() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or_default();
+        domains.extend(line.split_whitespace().map(str::to_string));
+    }
+    Ok(domains)
+}
+
+/// Serves the fake API until Ctrl-C, printing its settings in `.env` format so
+/// shell scripts can pick them up.
+#[cfg(feature = "testing")]
+async fn run_mock_server(
+    listen: std::net::SocketAddr,
+    domains: &[String],
+    rate_limit: Option<u32>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use crate::rate_limit::{EndpointClass, RateLimit};
+    use crate::testing::{API_KEY, FakePorkbun, SECRET_KEY};
+
+    let fake = FakePorkbun::bind(listen).await?;
+    for domain in domains {
+        fake.add_domain(domain);
+    }
+    if let Some(requests) = rate_limit {
+        let limit = RateLimit::new(requests, Duration::from_secs(60));
+        fake.simulate_rate_limit(EndpointClass::Default, limit);
+        fake.simulate_rate_limit(EndpointClass::CheckDomain, limit);
+    }
+    println!("BASE_URL={}", fake.base_url());
+    println!("API_KEY={}", API_KEY);
+    println!("SECRET_KEY={}", SECRET_KEY);
+    std::io::stdout().flush()?;
+    fake.run_until_ctrl_c().await?;
+    Ok(())
+}
+
 /// Runs the CLI application.
 ///
 /// This function is the entry point for the CLI application. It parses the command-line arguments,
@@ -175,8 +695,12 @@ fn ensure_input(msg: &str) -> bool {
 ///
 /// This function can return any error that may occur during the execution of the CLI commands.
 pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
-    // ensure that .env files are also supported
+    // ensure that .env files are also supported, falling back to the credentials
+    // stored by `login`
     dotenvy::dotenv().ok();
+    if let Some(path) = credentials_path() {
+        dotenvy::from_path(path).ok();
+    }
 
     let cli = Cli::parse();
 
@@ -196,15 +720,24 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
 
-    assert!(cli.api_key.is_some(), "API_KEY is not set");
-    assert!(cli.secret_key.is_some(), "SECRET_KEY is not set");
+    // pricing and login are public, every other command needs credentials
+    let needs_credentials = match cli.command {
+        None | Some(Commands::Pricing { .. }) | Some(Commands::Login { .. }) => false,
+        #[cfg(feature = "testing")]
+        Some(Commands::MockServer { .. }) => false,
+        _ => true,
+    };
+    let api_key = cli.api_key.as_deref().unwrap_or_default();
+    let secret_key = cli.secret_key.as_deref().unwrap_or_default();
+    if needs_credentials && api_key.is_empty() {
+        return Err("API_KEY is not set".into());
+    }
+    if needs_credentials && secret_key.is_empty() {
+        return Err("SECRET_KEY is not set".into());
+    }
 
-    let client = porkbunn_client::PorkbunnClient::new(
-        &cli.base_url,
-        &cli.url_version,
-        &cli.api_key.unwrap(),
-        &cli.secret_key.unwrap(),
-    );
+    let client =
+        porkbunn_client::PorkbunnClient::new(&cli.base_url, &cli.url_version, api_key, secret_key)?;
     match &cli.command {
         Some(Commands::CreateRecord {
             ttl,
@@ -212,6 +745,7 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
             domain,
             name,
             ip_address,
+            prio,
             delete_existing,
         }) => {
             tracing::debug!(
@@ -221,53 +755,499 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
                 ttl,
                 record_type.to_string()
             );
+            // reject malformed content before touching any existing record
+            let record = DnsRecord::parse(*record_type, ip_address, *prio)?;
             if *delete_existing {
-                let records = client.list_dns_records(name).await?;
-                for record in records.records {
-                    if record.name == *name && record.type_field == record_type.to_string() {
-                        if let Ok(id) = record.id.parse::<u64>() {
-                            tracing::info!("Deleting existing record with id {}", id);
-                            client.delete_dns_record(domain, id).await?;
-                        }
+                let records = client
+                    .retrieve_dns_records_by_name_type(domain, &record_type.to_string(), name)
+                    .await?;
+                let mut ids = records
+                    .records
+                    .iter()
+                    .filter_map(|r| r.id.parse::<u64>().ok());
+                // edit the first existing record in place instead of deleting and
+                // recreating it, so a failed request never leaves the name unresolved
+                if let Some(first) = ids.next() {
+                    for id in ids {
+                        tracing::info!("Deleting existing record with id {}", id);
+                        client.delete_dns_record(domain, id).await?;
                     }
+                    tracing::info!("Replacing existing record with id {}", first);
+                    let update = porkbunn_client::RecordUpdate {
+                        name: name.clone(),
+                        record,
+                        ttl: Some(*ttl),
+                        notes: None,
+                    };
+                    client
+                        .edit_dns_record(domain, first, &update)
+                        .await?
+                        .pretty_print();
+                    return Ok(());
                 }
             }
 
             client
-                .create_dns_record(
-                    domain,
-                    name,
-                    &record_type.to_string().to_uppercase(),
-                    ip_address,
-                    *ttl,
-                )
+                .create_dns_record(domain, name, &record, *ttl)
                 .await?
                 .pretty_print();
         }
         Some(Commands::DeleteRecord {
             domain,
             id,
+            name,
+            record_type,
             skip_confirm,
         }) => {
-            tracing::debug!("Deleting {} with id {}", domain, id);
-            if *skip_confirm {
-                client.delete_dns_record(domain, *id).await?.pretty_print();
-                return Ok(());
+            let name = name.clone().unwrap_or_default();
+            if !*skip_confirm {
+                let msg = match record_type {
+                    Some(record_type) => format!(
+                        "Are you sure you want to delete all {} records of {}? (y/n)",
+                        record_type,
+                        fqdn(&name, domain)
+                    ),
+                    None => "Are you sure you want to delete this record? (y/n)".to_string(),
+                };
+                if !ensure_input(&msg) {
+                    println!("Record not deleted");
+                    return Ok(());
+                }
+            }
+
+            match (id, record_type) {
+                (Some(id), _) => {
+                    tracing::debug!("Deleting {} with id {}", domain, id);
+                    client.delete_dns_record(domain, *id).await?.pretty_print();
+                }
+                (None, Some(record_type)) => {
+                    tracing::debug!("Deleting {} records of {}", record_type, domain);
+                    client
+                        .delete_dns_records_by_name_type(domain, &record_type.to_string(), &name)
+                        .await?
+                        .pretty_print();
+                }
+                (None, None) => unreachable!("clap requires either --id or --type"),
+            }
+        }
+        Some(Commands::EditRecord {
+            domain,
+            id,
+            name,
+            record_type,
+            content,
+            ttl,
+            prio,
+            notes,
+        }) => {
+            let name = name.clone().unwrap_or_default();
+            let records = match (id, record_type) {
+                (Some(id), _) => {
+                    tracing::debug!("Editing {} with id {}", domain, id);
+                    vec![client.get_dns_record(domain, *id).await?]
+                }
+                (None, Some(record_type)) => {
+                    tracing::debug!("Editing {} records of {}", record_type, domain);
+                    client
+                        .retrieve_dns_records_by_name_type(domain, &record_type.to_string(), &name)
+                        .await?
+                        .records
+                }
+                (None, None) => unreachable!("clap requires either --id or --type"),
+            };
+
+            if content.is_none() && records.len() > 1 {
+                return Err(format!(
+                    "{} records match, pass --content to set all of them",
+                    records.len()
+                )
+                .into());
+            }
+            let Some(record) = records.into_iter().next() else {
+                return Err(format!("No matching record found for {}", domain).into());
+            };
+
+            let existing_prio = record.prio.as_deref().and_then(|p| p.trim().parse().ok());
+            let update = porkbunn_client::RecordUpdate {
+                name: subdomain_of(&record.name, domain)?,
+                record: DnsRecord::parse(
+                    record.type_field.parse()?,
+                    content.as_deref().unwrap_or(&record.content),
+                    prio.or(existing_prio),
+                )?,
+                ttl: ttl.or(record.ttl.parse().ok()),
+                notes: notes.clone().or(record.notes),
+            };
+            match id {
+                Some(id) => client.edit_dns_record(domain, *id, &update).await?,
+                None => {
+                    client
+                        .edit_dns_records_by_name_type(domain, &update)
+                        .await?
+                }
+            }
+            .pretty_print();
+        }
+        Some(Commands::GetNs { domain }) => {
+            client.get_nameservers(domain).await?.pretty_print();
+        }
+        Some(Commands::SetNs {
+            domain,
+            nameservers,
+            skip_confirm,
+        }) => {
+            if !*skip_confirm {
+                let current = client.get_nameservers(domain).await?.ns;
+                println!("Current nameservers of {}: {}", domain, current.join(", "));
+                println!("New nameservers of {}: {}", domain, nameservers.join(", "));
+                if !ensure_input("Are you sure you want to update the nameservers? (y/n)") {
+                    println!("Nameservers not updated");
+                    return Ok(());
+                }
             }
+            client
+                .update_nameservers(domain, nameservers)
+                .await?
+                .pretty_print();
+        }
+        Some(Commands::AutoRenew {
+            state,
+            domains,
+            all,
+            filter,
+        }) => {
+            let enabled = *state == Toggle::On;
+            let domains = if !domains.is_empty() {
+                domains.clone()
+            } else if *all || !filter.is_empty() {
+                // leave out domains which are already in the requested state
+                client
+                    .list_domains(filter.label.is_some())
+                    .await?
+                    .domains
+                    .into_iter()
+                    .filter(|domain| filter.matches(domain) && domain.auto_renews() != enabled)
+                    .map(|domain| domain.domain)
+                    .collect()
+            } else {
+                return Err("Pass --domain, a filter or --all to select domains".into());
+            };
 
-            if ensure_input("Are you sure you want to delete this record? (y/n)") {
-                client.delete_dns_record(domain, *id).await?.pretty_print();
+            if domains.is_empty() {
+                println!("No domains to update");
+                return Ok(());
+            }
+            client
+                .update_auto_renew(&domains, enabled)
+                .await?
+                .pretty_print();
+        }
+        Some(Commands::Check { domains }) => {
+            let domains = if domains.is_empty() {
+                read_domains_from_stdin()?
             } else {
-                println!("Record not deleted");
+                domains.clone()
+            };
+
+            // checkDomain allows very few calls per window, so wait out the window
+            // whenever it is used up and retry once if a check is rate limited anyway
+            let mut pause = Duration::ZERO;
+            let mut failed = 0;
+            for domain in &domains {
+                tokio::time::sleep(pause).await;
+                let response = match client.check_domain(domain).await {
+                    Err(err @ PorkbunnError::RateLimited(_)) => {
+                        tracing::debug!("Check of {} was rate limited, retrying: {}", domain, err);
+                        let wait = err.retry_after().unwrap_or(DEFAULT_CHECK_PAUSE);
+                        tokio::time::sleep(wait.max(pause)).await;
+                        client.check_domain(domain).await
+                    }
+                    response => response,
+                };
+                match response {
+                    Ok(response) => {
+                        pause = response
+                            .limits
+                            .as_ref()
+                            .map_or(DEFAULT_CHECK_PAUSE, |limits| limits.wait());
+                        CheckRow {
+                            domain,
+                            availability: &response.response,
+                        }
+                        .pretty_print();
+                    }
+                    // the remaining checks would be rejected the same way
+                    Err(err) if err.is_auth() => return Err(err.into()),
+                    Err(err) => {
+                        tracing::error!("Failed to check {}: {}", domain, err);
+                        if matches!(err, PorkbunnError::RateLimited(_)) {
+                            pause = DEFAULT_CHECK_PAUSE;
+                        }
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 {
+                return Err(format!("{} of {} checks failed", failed, domains.len()).into());
+            }
+        }
+        Some(Commands::Dnssec { command }) => match command {
+            DnssecCommands::Create {
+                domain,
+                key_tag,
+                alg,
+                digest_type,
+                digest,
+            } => {
+                let record = porkbunn_client::DsRecord {
+                    key_tag: *key_tag,
+                    alg: *alg,
+                    digest_type: *digest_type,
+                    digest: digest.clone(),
+                };
+                client
+                    .create_ds_record(domain, &record)
+                    .await?
+                    .pretty_print();
+            }
+            DnssecCommands::List { domain } => {
+                for record in client.get_ds_records(domain).await?.records.values() {
+                    println!("{}", record.presentation(domain));
+                }
+            }
+            DnssecCommands::Delete {
+                domain,
+                key_tag,
+                skip_confirm,
+            } => {
+                if !*skip_confirm
+                    && !ensure_input(&format!(
+                        "Are you sure you want to delete the DS record with key tag {}? (y/n)",
+                        key_tag
+                    ))
+                {
+                    println!("DS record not deleted");
+                    return Ok(());
+                }
+                client
+                    .delete_ds_record(domain, *key_tag)
+                    .await?
+                    .pretty_print();
+            }
+        },
+        Some(Commands::Forward { command }) => match command {
+            ForwardCommands::Add {
+                domain,
+                subdomain,
+                location,
+                forward_type,
+                include_path,
+                wildcard,
+            } => {
+                let forward = porkbunn_client::UrlForward {
+                    subdomain: subdomain.clone().unwrap_or_default(),
+                    location: location.clone(),
+                    forward_type: *forward_type,
+                    include_path: *include_path,
+                    wildcard: *wildcard,
+                };
+                client
+                    .add_url_forward(domain, &forward)
+                    .await?
+                    .pretty_print();
+            }
+            ForwardCommands::List { domain } => {
+                client.list_url_forwards(domain).await?.pretty_print();
+            }
+            ForwardCommands::Delete {
+                domain,
+                id,
+                skip_confirm,
+            } => {
+                if !*skip_confirm
+                    && !ensure_input("Are you sure you want to delete this URL forward? (y/n)")
+                {
+                    println!("URL forward not deleted");
+                    return Ok(());
+                }
+                client.delete_url_forward(domain, *id).await?.pretty_print();
+            }
+        },
+        Some(Commands::Glue { command }) => match command {
+            GlueCommands::Create { domain, host, ips } => {
+                client.create_glue(domain, host, ips).await?.pretty_print();
+            }
+            GlueCommands::Update { domain, host, ips } => {
+                client.update_glue(domain, host, ips).await?.pretty_print();
+            }
+            GlueCommands::Delete {
+                domain,
+                host,
+                skip_confirm,
+            } => {
+                if !*skip_confirm
+                    && !ensure_input(&format!(
+                        "Are you sure you want to delete the glue record of {}? (y/n)",
+                        fqdn(host, domain)
+                    ))
+                {
+                    println!("Glue record not deleted");
+                    return Ok(());
+                }
+                client.delete_glue(domain, host).await?.pretty_print();
+            }
+            GlueCommands::Get { domain } => {
+                client.get_glue(domain).await?.pretty_print();
+            }
+        },
+        Some(Commands::Register {
+            domain,
+            max_price,
+            nameservers,
+            skip_confirm,
+        }) => {
+            let quote = client.check_domain(domain).await?.response;
+            if !quote.avail {
+                return Err(format!("{} is not available", domain).into());
+            }
+            if let Some(max_price) = max_price {
+                if quote.premium {
+                    return Err(format!("{} is a premium domain", domain).into());
+                }
+                if quote.price > *max_price {
+                    return Err(format!(
+                        "{} costs ${:.2}, more than the maximum of ${:.2}",
+                        domain, quote.price, max_price
+                    )
+                    .into());
+                }
+            }
+
+            println!(
+                "{} is available for ${:.2} for the first year, then ${:.2} per year",
+                domain, quote.price, quote.regular_price
+            );
+            if !*skip_confirm
+                && !ensure_input("Are you sure you want to register this domain? (y/n)")
+            {
+                println!("Domain not registered");
                 return Ok(());
             }
+
+            let cost = (quote.price * 100.0).round() as u64;
+            client.register_domain(domain, cost).await?.pretty_print();
+            if !nameservers.is_empty() {
+                client
+                    .update_nameservers(domain, nameservers)
+                    .await?
+                    .pretty_print();
+            }
+        }
+        Some(Commands::Ssl { command }) => match command {
+            SslCommands::Fetch { domain, output_dir } => {
+                let bundle = client.retrieve_ssl_bundle(domain).await?;
+                std::fs::create_dir_all(output_dir)?;
+                let files = [
+                    ("fullchain.pem", &bundle.certificate_chain, false),
+                    ("privkey.pem", &bundle.private_key, true),
+                    ("pubkey.pem", &bundle.public_key, false),
+                ];
+                for (name, content, private) in files {
+                    let path = output_dir.join(name);
+                    if write_if_changed(&path, content, private)? {
+                        println!("Wrote {}", path.display());
+                    } else {
+                        println!("Unchanged {}", path.display());
+                    }
+                }
+            }
+        },
+        Some(Commands::GetRecord { domain, id }) => {
+            client.get_dns_record(domain, *id).await?.pretty_print();
+        }
+        Some(Commands::ListDomains { filter }) => {
+            let mut response = client.list_domains(true).await?;
+            response.domains.retain(|domain| filter.matches(domain));
+            response.pretty_print();
+        }
+        Some(Commands::Login {
+            poll_interval,
+            timeout,
+        }) => {
+            let path = credentials_path().ok_or("Could not determine the config directory")?;
+            let request = client.request_api_key().await?;
+            println!("Open this URL to approve the new API key:");
+            println!("{}", request.auth_url);
+
+            let deadline = tokio::time::Instant::now() + Duration::from_secs(*timeout);
+            let credentials = loop {
+                match client.retrieve_api_key(&request.request_token).await {
+                    Ok(credentials) => break credentials,
+                    // the request is rejected until it has been approved
+                    Err(err) if err.api_failure().is_some() => {
+                        tracing::debug!("API key not approved yet: {}", err);
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+                if tokio::time::Instant::now() >= deadline {
+                    return Err("Timed out waiting for the API key to be approved".into());
+                }
+                tokio::time::sleep(Duration::from_secs(*poll_interval)).await;
+            };
+
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let content = format!(
+                "API_KEY={}\nSECRET_KEY={}\n",
+                credentials.api_key, credentials.secret_api_key
+            );
+            write_if_changed(&path, &content, true)?;
+            println!("Saved credentials to {}", path.display());
+        }
+        Some(Commands::Pricing { tlds, sort, markup }) => {
+            let tlds: Vec<String> = tlds
+                .iter()
+                .map(|tld| tld.trim_start_matches('.').to_lowercase())
+                .collect();
+            let pricing = client.get_pricing().await?.pricing;
+            let mut rows: Vec<PricingRow> = pricing
+                .iter()
+                .filter(|(tld, _)| tlds.is_empty() || tlds.contains(tld))
+                .map(|(tld, pricing)| PricingRow {
+                    tld,
+                    pricing,
+                    high_renewal: pricing.renewal_markup() >= *markup,
+                })
+                .collect();
+            match sort {
+                PricingSort::Tld => {}
+                PricingSort::Registration => {
+                    rows.sort_by(|a, b| a.pricing.registration.total_cmp(&b.pricing.registration))
+                }
+                PricingSort::Renewal => {
+                    rows.sort_by(|a, b| a.pricing.renewal.total_cmp(&b.pricing.renewal))
+                }
+                PricingSort::Transfer => {
+                    rows.sort_by(|a, b| a.pricing.transfer.total_cmp(&b.pricing.transfer))
+                }
+            }
+            rows.pretty_print();
         }
-        Some(Commands::ListDomains) => {
-            client.list_domains().await?.pretty_print();
+        Some(Commands::Ping) => {
+            println!("{}", client.ping().await?.your_ip);
         }
         Some(Commands::ListRecords { domain }) => {
             client.list_dns_records(domain).await?.pretty_print();
         }
+        #[cfg(feature = "testing")]
+        Some(Commands::MockServer {
+            listen,
+            domains,
+            rate_limit,
+        }) => {
+            run_mock_server(*listen, domains, *rate_limit).await?;
+        }
         None => {
             // print help and exit
             let _ = Cli::command().print_help();
@@ -275,3 +1255,101 @@ pub async fn run() -> Result<(), Box<dyn std::error::Error>> {
     };
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    fn domain(name: &str, expires_in_days: i64, labels: &[&str]) -> porkbunn_client::Domain {
+        let expires = chrono::Utc::now().naive_utc() + chrono::Duration::days(expires_in_days);
+        porkbunn_client::Domain {
+            domain: name.to_string(),
+            tld: name.rsplit('.').next().unwrap_or_default().to_string(),
+            expire_date: expires.format("%Y-%m-%d %H:%M:%S").to_string(),
+            labels: labels
+                .iter()
+                .map(|title| porkbunn_client::Label {
+                    title: title.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn domain_filters_match_labels_tld_and_expiry() {
+        let soon = domain("soon.com", 10, &["Clients"]);
+        let later = domain("later.dev", 100, &[]);
+        let unknown_expiry = porkbunn_client::Domain {
+            expire_date: "unknown".to_string(),
+            ..domain("unknown.com", 0, &[])
+        };
+
+        let filter = DomainFilter::default();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&soon) && filter.matches(&later) && filter.matches(&unknown_expiry));
+
+        let filter = DomainFilter {
+            label: Some("clients".to_string()),
+            ..Default::default()
+        };
+        assert!(filter.matches(&soon) && !filter.matches(&later));
+
+        let filter = DomainFilter {
+            tld: Some(".DEV".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&soon) && filter.matches(&later));
+
+        let filter = DomainFilter {
+            expiring_within: Some(30),
+            ..Default::default()
+        };
+        assert!(filter.matches(&soon));
+        assert!(!filter.matches(&later));
+        assert!(!filter.matches(&unknown_expiry));
+
+        // all conditions must hold
+        let filter = DomainFilter {
+            tld: Some("com".to_string()),
+            expiring_within: Some(30),
+            ..Default::default()
+        };
+        assert!(filter.matches(&soon) && !filter.matches(&later));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_files_are_only_readable_by_their_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("porkbun-rs-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("privkey.pem");
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+        assert!(write_if_changed(&path, "key", true).unwrap());
+        assert_eq!(mode(&path), 0o600);
+
+        // an unchanged file keeps its content but loses wider permissions
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(!write_if_changed(&path, "key", true).unwrap());
+        assert_eq!(mode(&path), 0o600);
+
+        // a symlink planted at the temporary path is replaced, not followed
+        let target = dir.join("target");
+        std::fs::write(&target, "").unwrap();
+        std::os::unix::fs::symlink(&target, dir.join("privkey.tmp")).unwrap();
+        assert!(write_if_changed(&path, "new key", true).unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new key");
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
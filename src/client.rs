@@ -1,11 +1,97 @@
-use crate::errors::PorkbunnError;
+use crate::errors::{ApiFailure, PorkbunnError};
+use crate::rate_limit::{EndpointClass, RateLimiter};
+use crate::redact::redact;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::hash::{BuildHasher, Hasher};
+use std::marker::PhantomData;
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone)]
 pub struct HTTPClient {
     client: reqwest::Client,
     base_url: reqwest::Url,
     version: String,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+}
+
+/// Controls how requests are retried after transient failures.
+///
+/// Connection failures, HTTP 429 and 5xx responses are retried with exponential
+/// backoff and full jitter, or after the delay a `Retry-After` header asks for.
+/// Only idempotent endpoints are retried unless `retry_non_idempotent` is set;
+/// requests which never reached the server because the connection could not be
+/// established are always retried.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// Upper bound of the delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay before any retry, unless `Retry-After` asks for more.
+    pub max_backoff: Duration,
+    /// Also retry endpoints like `dns/create`, which may apply twice if the
+    /// first attempt reached the server, and deletes, whose retry then fails
+    /// because the object is already gone.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy which never retries.
+    pub fn none() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Returns a random delay between zero and the exponential backoff for the
+    /// given retry, counting from 1.
+    fn backoff(&self, retry: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_backoff);
+        exponential.mul_f64(jitter())
+    }
+}
+
+/// Returns a pseudo random number in `[0, 1)`, good enough to spread retries.
+fn jitter() -> f64 {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    if let Ok(elapsed) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(elapsed.as_nanos());
+    }
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Parses a `Retry-After` header given either in seconds or as an HTTP date.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
 }
 
 #[derive(Deserialize, Serialize, Debug, Default)]
@@ -14,152 +100,144 @@ pub struct APIError {
     pub message: Option<String>,
 }
 
-/// Make a http request by providing a json-body
-#[macro_export]
-/// Macro for making a JSON request using the specified HTTP method, URL, and request body.
+/// An endpoint of the Porkbun API.
 ///
-/// # Arguments
+/// Implement this to call endpoints the crate does not cover yet and pass it to
+/// `PorkbunnClient::execute`, which adds the API credentials to the body, sends
+/// it and turns failed calls into errors like `PorkbunnError::NotFound`.
 ///
-/// * `$sel`: The selector for the HTTP client.
-/// * `$method`: The HTTP method to use for the request.
-/// * `$url`: The URL to send the request to.
-/// * `$body`: The request body.
+/// ```rust, no_run
+/// use porkbun_rs::client::Endpoint;
+/// use porkbun_rs::porkbunn_client::{PorkbunnClient, ResponseListDnsRecords};
+/// use serde::Serialize;
 ///
-/// # Returns
+/// #[derive(Serialize)]
+/// struct Body {}
 ///
-/// Returns a `Result` containing the JSON response if the request is successful, or an `APIError` if the request fails.
+/// struct RetrieveRecords {
+///     domain: String,
+///     body: Body,
+/// }
 ///
-/// # Examples
+/// impl Endpoint for RetrieveRecords {
+///     type Request = Body;
+///     type Response = ResponseListDnsRecords;
 ///
-/// ```rust
-/// # #[macro_use] extern crate porkbun_rs;
-/// # use reqwest::Method;
-/// # use porkbun_rs::client::APIError;
-/// # use porkbun_rs::errors::PorkbunnError;
-/// # use porkbun_rs::client::PorkbunClient;
-/// # use serde_json::Value;
-/// # use tracing::error;
-/// # #[cfg(feature = "debug")]
-/// # use serde_json::from_value;
-/// # #[cfg(not(feature = "debug"))]
-/// # use serde_json::from_value;
-/// #
-/// # struct MyHttpClient;
-/// # impl MyHttpClient {
-/// #     fn inner(&self, method: Method, url: &str) -> Result<reqwest::RequestBuilder, reqwest::Error> {
-/// #         unimplemented!()
-/// #     }
-/// # }
-/// #
-/// # impl PorkbunClient for MyHttpClient {
-/// #     fn http_client(&self) -> &MyHttpClient {
-/// #         self
-/// #     }
-/// # }
-/// #
-/// # fn main() -> Result<(), PorkbunnError> {
-/// #     let sel = MyHttpClient;
-/// #     let method = Method::GET;
-/// #     let url = "https://example.com";
-/// #     let body = serde_json::json!({});
-/// #
-/// let response = make_json_request!(sel, method, url, body);
-/// match response {
-///     Ok(json) => {
-///         // Handle successful response
+///     fn path(&self) -> String {
+///         format!("dns/retrieve/{}", self.domain)
 ///     }
-///     Err(error) => {
-///         // Handle API error
+///
+///     fn body(&self) -> &Body {
+///         &self.body
 ///     }
 /// }
-/// #
-/// #     Ok(())
+///
+/// # async fn run() -> Result<(), porkbun_rs::errors::PorkbunnError> {
+/// let client = PorkbunnClient::new("https://api.porkbun.com/api/json/", "v3", "pk1_...", "sk1_...")?;
+/// let endpoint = RetrieveRecords { domain: "example.com".to_string(), body: Body {} };
+/// let records = client.execute(&endpoint).await?;
+/// # Ok(())
 /// # }
 /// ```
-///
-/// Note: This macro requires the `reqwest`, `tracing`, `serde_json`, `APIError`, and `PorkbunnError` dependencies to be in scope.
-macro_rules! make_json_request {
-    ($sel:ident, $method:path, $url:expr, $body:ident) => {{
-        use reqwest;
-        use tracing::error;
-        use $crate::{client::APIError, errors::PorkbunnError};
-
-        tracing::debug!(
-            "make_json_request: method = {}, url = {} body = {:?}",
-            stringify!($method),
-            $url,
-            $body
-        );
-        let response: reqwest::Response = $sel
-            .http_client
-            .inner($method, $url)?
-            .json($body)
-            .send()
-            .await?;
-        let status_code = &response.status().as_u16();
-
-        if !(*status_code >= 200 && *status_code < 300) {
-            error!("status_code = {}", status_code);
-            error!("url queried = {}", $url);
-            let api_response: serde_json::Value = response.json().await?;
-            tracing::debug!("Received api response: {:#?}", api_response);
-            let api_response: APIError = serde_json::from_value(api_response).unwrap();
-            return Err(PorkbunnError::APIResponseError {
-                status: api_response.status.unwrap_or_default(),
-                message: api_response.message.unwrap_or_default(),
-            });
-        }
+pub trait Endpoint {
+    /// The JSON body sent to the endpoint, without the API credentials. It has to
+    /// serialize to a JSON object or to `null`.
+    type Request: Serialize;
 
-        #[cfg(feature = "debug")]
-        {
-            let res: serde_json::Value = response.json().await?;
-            tracing::debug!("Response {:?}", res);
-            Ok(serde_json::from_value(res)?)
-        }
+    /// The JSON body the endpoint returns on success.
+    type Response: DeserializeOwned;
+
+    /// The path of the endpoint relative to the API version, e.g. `dns/retrieve/example.com`.
+    fn path(&self) -> String;
+
+    /// The body of the request.
+    fn body(&self) -> &Self::Request;
+
+    /// Whether the API credentials are added to the body. Only a few endpoints,
+    /// like `pricing/get`, work without them.
+    fn authenticated(&self) -> bool {
+        true
+    }
 
-        #[cfg(not(feature = "debug"))]
-        Ok(response.json().await?)
-    }};
+    /// Whether sending the request twice has the same effect as sending it once,
+    /// which allows retrying it after transient failures.
+    fn idempotent(&self) -> bool {
+        false
+    }
 }
 
-/// Make a http request without json body.
-#[macro_export]
-macro_rules! make_request {
-    ($sel:ident, $method:path, $url:expr) => {{
-        use reqwest;
-        use serde_json::json;
-
-        let body = json!({
-            "apikey": $sel.api_key,
-            "secretapikey": $sel.api_secret,
-        });
-        let response: reqwest::Response = $sel.http_client.inner($method, $url)?.json(&body).send().await?;
-        use $crate::client::APIError;
-
-        let status_code = &response.status().as_u16();
-        tracing::debug!("Received http status code: {}", status_code);
-        tracing::debug!("Sending requests to url: {}", $url);
-
-        if !(*status_code >= 200 && *status_code < 300) {
-            let api_response: serde_json::Value = response.json().await?;
-            tracing::debug!("Received api response: {:#?}", api_response);
-            let api_response: APIError = serde_json::from_value(api_response).unwrap();
-            return Err(PorkbunnError::APIResponseError {
-                status: api_response.status.unwrap_or_default(),
-                message: api_response.message.unwrap_or_default(),
-            });
-        }
+/// A generic `Endpoint` built from a path and a body, with the response type
+/// picked by the caller.
+pub struct Call<B, R> {
+    path: String,
+    body: B,
+    authenticated: bool,
+    idempotent: bool,
+    response: PhantomData<fn() -> R>,
+}
 
-        #[cfg(feature = "debug")]
-        {
-            let res: serde_json::Value = response.json().await?;
-            tracing::debug!("Response {:?}", res);
-            Ok(serde_json::from_value(res)?)
+impl<B, R> Call<B, R>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    /// Creates an authenticated call to `path` sending `body`.
+    pub fn new<S: Into<String>>(path: S, body: B) -> Call<B, R> {
+        Call {
+            path: path.into(),
+            body,
+            authenticated: true,
+            idempotent: false,
+            response: PhantomData,
         }
+    }
+
+    /// Sends the call without the API credentials.
+    pub fn unauthenticated(mut self) -> Call<B, R> {
+        self.authenticated = false;
+        self
+    }
 
-        #[cfg(not(feature = "debug"))]
-        Ok(response.json().await?)
-    }};
+    /// Marks the call as safe to retry after transient failures.
+    pub fn idempotent(mut self) -> Call<B, R> {
+        self.idempotent = true;
+        self
+    }
+}
+
+impl<R> Call<serde_json::Value, R>
+where
+    R: DeserializeOwned,
+{
+    /// Creates an authenticated call to `path` whose body only holds the API credentials.
+    pub fn empty<S: Into<String>>(path: S) -> Call<serde_json::Value, R> {
+        Call::new(path, serde_json::json!({}))
+    }
+}
+
+impl<B, R> Endpoint for Call<B, R>
+where
+    B: Serialize,
+    R: DeserializeOwned,
+{
+    type Request = B;
+    type Response = R;
+
+    fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    fn body(&self) -> &B {
+        &self.body
+    }
+
+    fn authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    fn idempotent(&self) -> bool {
+        self.idempotent
+    }
 }
 
 /// Represents an HTTP client for making requests to a specific base URL and API version.
@@ -172,21 +250,43 @@ macro_rules! make_request {
 ///
 /// This implementation is an internal detail of the crate and is not intended to be used directly by end-users.
 impl HTTPClient {
-    pub fn new<S, T>(base_url: S, client: reqwest::Client, version: T) -> HTTPClient
+    pub fn new<S, T>(
+        base_url: S,
+        client: reqwest::Client,
+        version: T,
+    ) -> Result<HTTPClient, PorkbunnError>
     where
         S: Into<String>,
         T: Into<String>,
     {
-        let parsed_url =
-            reqwest::Url::parse(&base_url.into()).expect("Failed to parse the base_url");
+        // without a trailing slash `join` would replace the last path segment
+        let mut base_url = base_url.into();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        let parsed_url = reqwest::Url::parse(&base_url)?;
 
         let ver = format!("{}/", version.into().replace('/', ""));
         tracing::debug!("API Version is {}", &ver);
-        HTTPClient {
+        Ok(HTTPClient {
             base_url: parsed_url,
             client,
             version: ver,
-        }
+            retry_policy: RetryPolicy::default(),
+            rate_limiter: RateLimiter::default(),
+        })
+    }
+
+    /// Replaces the default `RetryPolicy`.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> HTTPClient {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Throttles requests with `rate_limiter`, which is shared with its clones.
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> HTTPClient {
+        self.rate_limiter = rate_limiter;
+        self
     }
 
     pub(crate) fn inner(
@@ -209,4 +309,100 @@ impl HTTPClient {
             };
         request_with_url_and_header
     }
+
+    /// Sends `body` to `query_url` and deserializes the response, retrying
+    /// transient failures according to the `RetryPolicy`.
+    ///
+    /// Failed calls are returned as one of the API variants of `PorkbunnError`.
+    pub(crate) async fn post_json<R>(
+        &self,
+        query_url: &str,
+        body: &serde_json::Value,
+        idempotent: bool,
+    ) -> Result<R, PorkbunnError>
+    where
+        R: DeserializeOwned,
+    {
+        tracing::debug!("post_json: url = {} body = {}", query_url, redact(body));
+        let policy = &self.retry_policy;
+        let class = EndpointClass::of(query_url);
+        let mut attempt = 1;
+        loop {
+            self.rate_limiter.acquire(class).await;
+            let result = match self
+                .inner(reqwest::Method::POST, query_url)?
+                .json(body)
+                .send()
+                .await
+            {
+                Ok(response) => self.read_response(response, query_url).await,
+                Err(err) => Err(err.into()),
+            };
+            let err = match result {
+                Ok(response) => return Ok(response),
+                Err(err) => err,
+            };
+
+            let retry = match &err {
+                // the request never reached the server, so retrying is always safe
+                PorkbunnError::ReqwestError(e) if e.is_connect() => true,
+                err => err.is_retryable() && (idempotent || policy.retry_non_idempotent),
+            };
+            if !retry || attempt >= policy.max_attempts {
+                return Err(err);
+            }
+            let wait = err.retry_after().unwrap_or_else(|| policy.backoff(attempt));
+            tracing::warn!(
+                "Attempt {} of {} to {} failed ({}), retrying in {:?}",
+                attempt,
+                policy.max_attempts,
+                query_url,
+                err,
+                wait
+            );
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    /// Deserializes a response, turning non-2xx responses and bodies with an
+    /// `ERROR` status into errors.
+    async fn read_response<R>(
+        &self,
+        response: reqwest::Response,
+        query_url: &str,
+    ) -> Result<R, PorkbunnError>
+    where
+        R: DeserializeOwned,
+    {
+        let status_code = response.status().as_u16();
+        tracing::debug!("Received http status code: {}", status_code);
+        let retry_after = retry_after(&response);
+        let body = response.text().await?;
+
+        #[cfg(feature = "debug")]
+        tracing::debug!("Response {}", crate::redact::redact_body(&body));
+
+        let api_error: Option<APIError> = serde_json::from_str(&body).ok();
+        let failed = api_error
+            .as_ref()
+            .and_then(|e| e.status.as_deref())
+            .is_some_and(|status| status.eq_ignore_ascii_case("ERROR"));
+        if !(200..300).contains(&status_code) || failed {
+            tracing::error!("status_code = {}", status_code);
+            tracing::error!("url queried = {}", query_url);
+            let api_error = api_error.unwrap_or_default();
+            return Err(ApiFailure {
+                endpoint: query_url.to_string(),
+                http_status: status_code,
+                status: api_error.status,
+                message: api_error.message,
+                body,
+                retry_after,
+            }
+            .into_error());
+        }
+
+        Ok(serde_json::from_str(&body)?)
+    }
 }
@@ -0,0 +1,679 @@
+use crate::errors::PorkbunnError;
+use crate::porkbunn_client::Record;
+use crate::serde_ext::option_from_str_or_number;
+use serde::{Deserialize, Serialize};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+/// DNS record types supported by Porkbun.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RecordType {
+    A,
+    Mx,
+    Cname,
+    Alias,
+    Txt,
+    Ns,
+    Aaaa,
+    Srv,
+    Tlsa,
+    Caa,
+    Https,
+    Svcb,
+}
+
+impl std::fmt::Display for RecordType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let v = match self {
+            RecordType::A => "A".to_string(),
+            RecordType::Mx => "MX".to_string(),
+            RecordType::Cname => "CNAME".to_string(),
+            RecordType::Alias => "ALIAS".to_string(),
+            RecordType::Txt => "TXT".to_string(),
+            RecordType::Ns => "NS".to_string(),
+            RecordType::Aaaa => "AAAA".to_string(),
+            RecordType::Srv => "SRV".to_string(),
+            RecordType::Tlsa => "TLSA".to_string(),
+            RecordType::Caa => "CAA".to_string(),
+            RecordType::Https => "HTTPS".to_string(),
+            RecordType::Svcb => "SVCB".to_string(),
+        };
+        write!(f, "{}", v)
+    }
+}
+
+impl FromStr for RecordType {
+    type Err = PorkbunnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <RecordType as clap::ValueEnum>::from_str(s, true)
+            .map_err(|_| PorkbunnError::ValidationError(format!("unsupported record type {:?}", s)))
+    }
+}
+
+/// The content of a DNS record, typed per record type.
+///
+/// Porkbun transfers a record as its type, a `content` string and, for MX and
+/// SRV records, a separate `prio`. `DnsRecord::parse` and the `content`/`prio`
+/// methods convert between the two, validating the content on the way.
+/// Records of types without a typed variant, e.g. SSHFP, are kept verbatim as
+/// `DnsRecord::Other` when read from the API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "WireRecord", into = "WireRecord")]
+pub enum DnsRecord {
+    A(Ipv4Addr),
+    Aaaa(Ipv6Addr),
+    Mx {
+        prio: u16,
+        host: String,
+    },
+    Cname(String),
+    Alias(String),
+    Txt(String),
+    Ns(String),
+    Srv {
+        prio: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+    Tlsa {
+        usage: u8,
+        selector: u8,
+        matching_type: u8,
+        /// Hex encoded certificate association data.
+        data: String,
+    },
+    Caa {
+        flags: u8,
+        tag: String,
+        value: String,
+    },
+    Https {
+        priority: u16,
+        target: String,
+        /// Space separated SvcParams, e.g. `alpn=h2,h3`.
+        params: String,
+    },
+    Svcb {
+        priority: u16,
+        target: String,
+        /// Space separated SvcParams, e.g. `alpn=h2,h3`.
+        params: String,
+    },
+    /// A record of a type not listed in `RecordType`, with its content unchecked.
+    Other {
+        record_type: String,
+        content: String,
+        prio: Option<u16>,
+    },
+}
+
+/// A DNS record to create, or the new state of one to update.
+///
+/// Serializes to the body of Porkbun's `dns/create`, `dns/edit` and
+/// `dns/editByNameType` endpoints. Updates replace the record wholesale, so
+/// `name` and `record` must always be provided. `None` for `ttl` or `notes`
+/// leaves the provider default in place.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordUpdate {
+    /// Subdomain of the record, without the domain itself. Empty for the root domain.
+    pub name: String,
+    #[serde(flatten)]
+    pub record: DnsRecord,
+    #[serde(default, deserialize_with = "option_from_str_or_number")]
+    pub ttl: Option<u32>,
+    pub notes: Option<String>,
+}
+
+/// A DNS record as transferred by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WireRecord {
+    #[serde(rename = "type")]
+    record_type: String,
+    content: String,
+    #[serde(default, deserialize_with = "option_from_str_or_number")]
+    prio: Option<u16>,
+}
+
+impl TryFrom<WireRecord> for DnsRecord {
+    type Error = PorkbunnError;
+
+    fn try_from(wire: WireRecord) -> Result<Self, Self::Error> {
+        DnsRecord::from_wire(&wire.record_type, &wire.content, wire.prio)
+    }
+}
+
+impl From<DnsRecord> for WireRecord {
+    fn from(record: DnsRecord) -> Self {
+        WireRecord {
+            record_type: record.type_name(),
+            content: record.content(),
+            prio: record.prio(),
+        }
+    }
+}
+
+impl TryFrom<&Record> for DnsRecord {
+    type Error = PorkbunnError;
+
+    fn try_from(record: &Record) -> Result<Self, Self::Error> {
+        let prio = match record.prio.as_deref().map(str::trim) {
+            None | Some("") => None,
+            Some(prio) => Some(prio.parse().map_err(|_| {
+                PorkbunnError::ValidationError(format!("invalid priority {:?}", prio))
+            })?),
+        };
+        DnsRecord::from_wire(&record.type_field, &record.content, prio)
+    }
+}
+
+impl DnsRecord {
+    /// Like `parse`, but keeps records of a type not listed in `RecordType` as
+    /// `DnsRecord::Other` instead of rejecting them.
+    fn from_wire(
+        record_type: &str,
+        content: &str,
+        prio: Option<u16>,
+    ) -> Result<DnsRecord, PorkbunnError> {
+        match record_type.parse() {
+            Ok(record_type) => DnsRecord::parse(record_type, content, prio),
+            Err(_) if !record_type.is_empty() => Ok(DnsRecord::Other {
+                record_type: record_type.to_string(),
+                content: content.to_string(),
+                prio,
+            }),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Parses and validates the wire format of a record.
+    ///
+    /// # Arguments
+    ///
+    /// * `record_type` - The type of the record.
+    /// * `content` - The content as sent to and returned by the API.
+    /// * `prio` - The priority, required for MX and SRV records. For SRV records it
+    ///   may also be given as the first of four fields in `content`.
+    ///
+    /// # Returns
+    ///
+    /// The typed record, or a `PorkbunnError::ValidationError` describing why the
+    /// content is malformed.
+    pub fn parse(
+        record_type: RecordType,
+        content: &str,
+        prio: Option<u16>,
+    ) -> Result<DnsRecord, PorkbunnError> {
+        // TXT content is kept verbatim, leading and trailing spaces may matter
+        let raw = content;
+        let content = content.trim();
+        let fields: Vec<&str> = content.split_whitespace().collect();
+        let record = match record_type {
+            RecordType::A => DnsRecord::A(parse_field(content, "IPv4 address")?),
+            RecordType::Aaaa => DnsRecord::Aaaa(parse_field(content, "IPv6 address")?),
+            RecordType::Mx => DnsRecord::Mx {
+                prio: prio.ok_or_else(|| invalid("MX records need a priority"))?,
+                // `.` is a null MX, RFC 7505
+                host: target(content)?,
+            },
+            RecordType::Cname => DnsRecord::Cname(hostname(content)?),
+            RecordType::Alias => DnsRecord::Alias(hostname(content)?),
+            RecordType::Ns => DnsRecord::Ns(hostname(content)?),
+            RecordType::Txt => {
+                if raw.is_empty() {
+                    return Err(invalid("TXT records need content"));
+                }
+                DnsRecord::Txt(raw.to_string())
+            }
+            RecordType::Srv => {
+                let (prio, fields) = match (prio, fields.as_slice()) {
+                    (Some(prio), [_, _, _]) => (prio, &fields[..]),
+                    (None, [prio, rest @ ..]) if rest.len() == 3 => {
+                        (parse_field(prio, "SRV priority")?, rest)
+                    }
+                    _ => {
+                        return Err(invalid(
+                            "SRV records need a priority and `weight port target` content",
+                        ));
+                    }
+                };
+                DnsRecord::Srv {
+                    prio,
+                    weight: parse_field(fields[0], "SRV weight")?,
+                    port: parse_field(fields[1], "SRV port")?,
+                    target: target(fields[2])?,
+                }
+            }
+            RecordType::Tlsa => {
+                let [usage, selector, matching_type, data] = fields[..] else {
+                    return Err(invalid(
+                        "TLSA records need `usage selector matching_type data` content",
+                    ));
+                };
+                let usage: u8 = parse_field(usage, "TLSA usage")?;
+                let selector: u8 = parse_field(selector, "TLSA selector")?;
+                let matching_type: u8 = parse_field(matching_type, "TLSA matching type")?;
+                if usage > 3
+                    || selector > 1
+                    || matching_type > 2
+                    || !data.chars().all(|c| c.is_ascii_hexdigit())
+                {
+                    return Err(invalid(&format!("invalid TLSA content {:?}", content)));
+                }
+                DnsRecord::Tlsa {
+                    usage,
+                    selector,
+                    matching_type,
+                    data: data.to_string(),
+                }
+            }
+            RecordType::Caa => {
+                let mut parts = content.splitn(3, char::is_whitespace);
+                let (Some(flags), Some(tag), Some(value)) =
+                    (parts.next(), parts.next(), parts.next())
+                else {
+                    return Err(invalid("CAA records need `flags tag value` content"));
+                };
+                let tag = tag.to_string();
+                if tag.is_empty() || !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+                    return Err(invalid(&format!("invalid CAA tag {:?}", tag)));
+                }
+                DnsRecord::Caa {
+                    flags: parse_field(flags, "CAA flags")?,
+                    tag,
+                    value: value.trim().trim_matches('"').to_string(),
+                }
+            }
+            RecordType::Https | RecordType::Svcb => {
+                let [priority, target, ..] = fields[..] else {
+                    return Err(invalid(&format!(
+                        "{} records need `priority target [params]` content",
+                        record_type
+                    )));
+                };
+                let priority = parse_field(priority, "SvcPriority")?;
+                let target = self::target(target)?;
+                let params = fields[2..].join(" ");
+                if record_type == RecordType::Https {
+                    DnsRecord::Https {
+                        priority,
+                        target,
+                        params,
+                    }
+                } else {
+                    DnsRecord::Svcb {
+                        priority,
+                        target,
+                        params,
+                    }
+                }
+            }
+        };
+        Ok(record)
+    }
+
+    /// Returns the type of the record, `None` for `DnsRecord::Other`.
+    pub fn record_type(&self) -> Option<RecordType> {
+        let record_type = match self {
+            DnsRecord::A(_) => RecordType::A,
+            DnsRecord::Aaaa(_) => RecordType::Aaaa,
+            DnsRecord::Mx { .. } => RecordType::Mx,
+            DnsRecord::Cname(_) => RecordType::Cname,
+            DnsRecord::Alias(_) => RecordType::Alias,
+            DnsRecord::Txt(_) => RecordType::Txt,
+            DnsRecord::Ns(_) => RecordType::Ns,
+            DnsRecord::Srv { .. } => RecordType::Srv,
+            DnsRecord::Tlsa { .. } => RecordType::Tlsa,
+            DnsRecord::Caa { .. } => RecordType::Caa,
+            DnsRecord::Https { .. } => RecordType::Https,
+            DnsRecord::Svcb { .. } => RecordType::Svcb,
+            DnsRecord::Other { .. } => return None,
+        };
+        Some(record_type)
+    }
+
+    /// Returns the `type` field sent to the API, e.g. `MX`.
+    pub fn type_name(&self) -> String {
+        match self {
+            DnsRecord::Other { record_type, .. } => record_type.clone(),
+            _ => self
+                .record_type()
+                .map_or_else(String::new, |t| t.to_string()),
+        }
+    }
+
+    /// Returns the `content` field sent to the API.
+    pub fn content(&self) -> String {
+        match self {
+            DnsRecord::A(ip) => ip.to_string(),
+            DnsRecord::Aaaa(ip) => ip.to_string(),
+            DnsRecord::Mx { host, .. } => host.clone(),
+            DnsRecord::Cname(host) | DnsRecord::Alias(host) | DnsRecord::Ns(host) => host.clone(),
+            DnsRecord::Txt(text) => text.clone(),
+            DnsRecord::Srv {
+                weight,
+                port,
+                target,
+                ..
+            } => format!("{} {} {}", weight, port, target),
+            DnsRecord::Tlsa {
+                usage,
+                selector,
+                matching_type,
+                data,
+            } => format!("{} {} {} {}", usage, selector, matching_type, data),
+            DnsRecord::Caa { flags, tag, value } => format!("{} {} \"{}\"", flags, tag, value),
+            DnsRecord::Https {
+                priority,
+                target,
+                params,
+            }
+            | DnsRecord::Svcb {
+                priority,
+                target,
+                params,
+            } => format!("{} {} {}", priority, target, params)
+                .trim_end()
+                .to_string(),
+            DnsRecord::Other { content, .. } => content.clone(),
+        }
+    }
+
+    /// Returns the `prio` field sent to the API, which only MX and SRV records have.
+    pub fn prio(&self) -> Option<u16> {
+        match self {
+            DnsRecord::Mx { prio, .. } | DnsRecord::Srv { prio, .. } => Some(*prio),
+            DnsRecord::Other { prio, .. } => *prio,
+            _ => None,
+        }
+    }
+}
+
+fn invalid(msg: &str) -> PorkbunnError {
+    PorkbunnError::ValidationError(msg.to_string())
+}
+
+fn parse_field<T: FromStr>(value: &str, what: &str) -> Result<T, PorkbunnError> {
+    value
+        .parse()
+        .map_err(|_| invalid(&format!("invalid {} {:?}", what, value)))
+}
+
+/// Validates the target of an MX, SRV, HTTPS or SVCB record, where `.` means
+/// that the service is not available.
+fn target(value: &str) -> Result<String, PorkbunnError> {
+    if value == "." {
+        return Ok(value.to_string());
+    }
+    hostname(value)
+}
+
+/// Validates a host name as used in the content of CNAME, MX, NS and similar records.
+fn hostname(value: &str) -> Result<String, PorkbunnError> {
+    let name = value.strip_suffix('.').unwrap_or(value);
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= 63
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    if name.is_empty() || name.len() > 253 || !name.split('.').all(valid_label) {
+        return Err(invalid(&format!("invalid host name {:?}", value)));
+    }
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_the_wire_format() {
+        let mx: DnsRecord =
+            serde_json::from_str(r#"{"type":"MX","content":"mail.example.com","prio":"10"}"#)
+                .unwrap();
+        assert_eq!(
+            mx,
+            DnsRecord::Mx {
+                prio: 10,
+                host: "mail.example.com".to_string()
+            }
+        );
+
+        let mx: DnsRecord =
+            serde_json::from_str(r#"{"type":"MX","content":"mail.example.com","prio":10}"#)
+                .unwrap();
+        assert_eq!(mx.prio(), Some(10));
+
+        for prio in [r#""""#, "null", r#""0""#] {
+            let json = format!(r#"{{"type":"A","content":"192.0.2.1","prio":{}}}"#, prio);
+            let a: DnsRecord = serde_json::from_str(&json).unwrap();
+            assert_eq!(a, DnsRecord::A(Ipv4Addr::new(192, 0, 2, 1)));
+        }
+        let a: DnsRecord = serde_json::from_str(r#"{"type":"A","content":"192.0.2.1"}"#).unwrap();
+        assert_eq!(a.prio(), None);
+    }
+
+    #[test]
+    fn deserializes_record_updates() {
+        let update: RecordUpdate = serde_json::from_str(
+            r#"{"name":"mail","type":"MX","content":"mail.example.com","prio":"10","ttl":"600","notes":null}"#,
+        )
+        .unwrap();
+        assert_eq!(update.record.prio(), Some(10));
+        assert_eq!(update.ttl, Some(600));
+    }
+
+    /// Parses `content`, checks the result and that it converts back unchanged.
+    fn round_trip(record_type: RecordType, content: &str, prio: Option<u16>, expected: DnsRecord) {
+        let record = DnsRecord::parse(record_type, content, prio).unwrap();
+        assert_eq!(record, expected);
+        assert_eq!(record.record_type(), Some(record_type));
+        assert_eq!(record.content(), content);
+        assert_eq!(record.prio(), prio);
+        assert_eq!(
+            DnsRecord::parse(record_type, &record.content(), record.prio()).unwrap(),
+            record
+        );
+    }
+
+    #[test]
+    fn round_trips_every_record_type() {
+        round_trip(
+            RecordType::A,
+            "192.0.2.1",
+            None,
+            DnsRecord::A(Ipv4Addr::new(192, 0, 2, 1)),
+        );
+        round_trip(
+            RecordType::Aaaa,
+            "2001:db8::1",
+            None,
+            DnsRecord::Aaaa("2001:db8::1".parse().unwrap()),
+        );
+        round_trip(
+            RecordType::Mx,
+            "mail.example.com",
+            Some(10),
+            DnsRecord::Mx {
+                prio: 10,
+                host: "mail.example.com".to_string(),
+            },
+        );
+        round_trip(
+            RecordType::Cname,
+            "example.com.",
+            None,
+            DnsRecord::Cname("example.com.".to_string()),
+        );
+        round_trip(
+            RecordType::Alias,
+            "lb.example.net",
+            None,
+            DnsRecord::Alias("lb.example.net".to_string()),
+        );
+        round_trip(
+            RecordType::Txt,
+            "v=spf1 -all",
+            None,
+            DnsRecord::Txt("v=spf1 -all".to_string()),
+        );
+        round_trip(
+            RecordType::Ns,
+            "ns1.example.com",
+            None,
+            DnsRecord::Ns("ns1.example.com".to_string()),
+        );
+        round_trip(
+            RecordType::Srv,
+            "5 5060 sip.example.com",
+            Some(10),
+            DnsRecord::Srv {
+                prio: 10,
+                weight: 5,
+                port: 5060,
+                target: "sip.example.com".to_string(),
+            },
+        );
+        round_trip(
+            RecordType::Tlsa,
+            "3 1 1 0c72ac70b745ac19998811b131d662c9ac69dbdbe7cb23e5b514b56664c5d3d6",
+            None,
+            DnsRecord::Tlsa {
+                usage: 3,
+                selector: 1,
+                matching_type: 1,
+                data: "0c72ac70b745ac19998811b131d662c9ac69dbdbe7cb23e5b514b56664c5d3d6"
+                    .to_string(),
+            },
+        );
+        round_trip(
+            RecordType::Caa,
+            "0 issue \"letsencrypt.org\"",
+            None,
+            DnsRecord::Caa {
+                flags: 0,
+                tag: "issue".to_string(),
+                value: "letsencrypt.org".to_string(),
+            },
+        );
+        round_trip(
+            RecordType::Https,
+            "1 . alpn=h2,h3",
+            None,
+            DnsRecord::Https {
+                priority: 1,
+                target: ".".to_string(),
+                params: "alpn=h2,h3".to_string(),
+            },
+        );
+        round_trip(
+            RecordType::Svcb,
+            "0 svc.example.com",
+            None,
+            DnsRecord::Svcb {
+                priority: 0,
+                target: "svc.example.com".to_string(),
+                params: String::new(),
+            },
+        );
+    }
+
+    #[test]
+    fn keeps_txt_content_verbatim() {
+        round_trip(
+            RecordType::Txt,
+            "  padded value ",
+            None,
+            DnsRecord::Txt("  padded value ".to_string()),
+        );
+        assert!(DnsRecord::parse(RecordType::Txt, "", None).is_err());
+    }
+
+    #[test]
+    fn accepts_null_targets() {
+        round_trip(
+            RecordType::Mx,
+            ".",
+            Some(0),
+            DnsRecord::Mx {
+                prio: 0,
+                host: ".".to_string(),
+            },
+        );
+        round_trip(
+            RecordType::Srv,
+            "0 0 .",
+            Some(0),
+            DnsRecord::Srv {
+                prio: 0,
+                weight: 0,
+                port: 0,
+                target: ".".to_string(),
+            },
+        );
+        assert!(DnsRecord::parse(RecordType::Cname, ".", None).is_err());
+    }
+
+    #[test]
+    fn takes_the_srv_priority_from_the_content() {
+        let record = DnsRecord::parse(RecordType::Srv, "10 5 5060 sip.example.com", None).unwrap();
+        assert_eq!(record.prio(), Some(10));
+        assert_eq!(record.content(), "5 5060 sip.example.com");
+    }
+
+    #[test]
+    fn rejects_malformed_content() {
+        let invalid = [
+            (RecordType::A, "2001:db8::1", None),
+            (RecordType::Aaaa, "192.0.2.1", None),
+            (RecordType::Mx, "mail.example.com", None),
+            (RecordType::Cname, "-bad-.example.com", None),
+            (RecordType::Srv, "5 5060", Some(10)),
+            (RecordType::Tlsa, "4 1 1 abcd", None),
+            (RecordType::Tlsa, "3 1 1 xyz", None),
+            (RecordType::Caa, "0 is-sue \"ca.example\"", None),
+            (RecordType::Https, "1", None),
+        ];
+        for (record_type, content, prio) in invalid {
+            let err = DnsRecord::parse(record_type, content, prio).unwrap_err();
+            assert!(
+                matches!(err, PorkbunnError::ValidationError(_)),
+                "{} {:?}",
+                record_type,
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_records_of_other_types() {
+        let json = r#"{"type":"SSHFP","content":"4 2 123456789abcdef","prio":"0"}"#;
+        let record: DnsRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            record,
+            DnsRecord::Other {
+                record_type: "SSHFP".to_string(),
+                content: "4 2 123456789abcdef".to_string(),
+                prio: Some(0),
+            }
+        );
+        assert_eq!(record.record_type(), None);
+        assert_eq!(record.type_name(), "SSHFP");
+        assert_eq!(
+            serde_json::to_value(&record).unwrap(),
+            serde_json::json!({"type": "SSHFP", "content": "4 2 123456789abcdef", "prio": 0})
+        );
+
+        // known types are still validated
+        let json = r#"{"type":"A","content":"not an address"}"#;
+        assert!(serde_json::from_str::<DnsRecord>(json).is_err());
+        assert!(serde_json::from_str::<DnsRecord>(r#"{"type":"","content":"x"}"#).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use std::io;
+use std::time::Duration;
 use thiserror::Error;
 use url::ParseError;
 
@@ -22,6 +23,150 @@ pub enum PorkbunnError {
     #[error("Failed during Serde operation")]
     SerdeError(#[from] serde_json::Error),
 
-    #[error("Failed during parsing APIResponse: {message:?} and status {status:?}")]
-    APIResponseError { status: String, message: String },
+    /// The API key or secret was rejected.
+    #[error("Authentication failed: {0}")]
+    Unauthorized(ApiFailure),
+
+    /// The domain has not been opted in to API access in the Porkbun dashboard.
+    #[error("Domain is not opted in to API access: {0}")]
+    DomainNotOptedIn(ApiFailure),
+
+    /// Porkbun throttled the request.
+    #[error("Rate limit exceeded: {0}")]
+    RateLimited(ApiFailure),
+
+    /// The domain, record or other object does not exist.
+    #[error("Not found: {0}")]
+    NotFound(ApiFailure),
+
+    /// Porkbun rejected the request as invalid.
+    #[error("Invalid request: {0}")]
+    InvalidRequest(ApiFailure),
+
+    /// Any other failed API call, e.g. a 5xx or a non-JSON body from a proxy.
+    #[error("API request failed: {0}")]
+    APIResponseError(ApiFailure),
+
+    #[error("Invalid input: {0}")]
+    ValidationError(String),
+
+    #[error("No DNS record with id {id} found for {domain}")]
+    RecordNotFound { domain: String, id: u64 },
+
+    #[error("Zone {0} not found")]
+    ZoneNotFound(String),
+}
+
+/// Details of a failed API call, kept to tell failures apart and for debugging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiFailure {
+    /// Path of the endpoint, e.g. `dns/create/example.com`.
+    pub endpoint: String,
+    /// HTTP status code of the response.
+    pub http_status: u16,
+    /// The `status` field of the JSON body, usually `ERROR`, if the body was JSON.
+    pub status: Option<String>,
+    /// The `message` field of the JSON body, if the body was JSON.
+    pub message: Option<String>,
+    /// The raw response body.
+    pub body: String,
+    /// The delay requested by a `Retry-After` header.
+    pub retry_after: Option<Duration>,
+}
+
+impl std::fmt::Display for ApiFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} returned HTTP {}", self.endpoint, self.http_status)?;
+        match &self.message {
+            Some(message) => write!(f, ": {}", message),
+            None if self.body.trim().is_empty() => Ok(()),
+            // most likely an HTML error page, only show its beginning
+            None => write!(
+                f,
+                ": {}",
+                self.body.trim().chars().take(200).collect::<String>()
+            ),
+        }
+    }
+}
+
+impl ApiFailure {
+    /// Turns the failure into the matching `PorkbunnError` variant, based on the
+    /// HTTP status and the message, as Porkbun answers most errors with a 400.
+    pub fn into_error(self) -> PorkbunnError {
+        let message = self.message.as_deref().unwrap_or_default().to_lowercase();
+        if message.contains("opted in") {
+            PorkbunnError::DomainNotOptedIn(self)
+        } else if self.http_status == 401
+            || self.http_status == 403
+            || message.contains("api key")
+            || message.contains("authentication")
+        {
+            PorkbunnError::Unauthorized(self)
+        } else if self.http_status == 429 || message.contains("rate limit") {
+            PorkbunnError::RateLimited(self)
+        } else if self.http_status >= 500 {
+            // server errors are transient whatever their message says
+            PorkbunnError::APIResponseError(self)
+        } else if self.http_status == 404
+            || message.contains("not found")
+            || message.contains("could not find")
+            || message.contains("does not exist")
+        {
+            PorkbunnError::NotFound(self)
+        } else if (400..500).contains(&self.http_status) || self.message.is_some() {
+            PorkbunnError::InvalidRequest(self)
+        } else {
+            PorkbunnError::APIResponseError(self)
+        }
+    }
+}
+
+impl PorkbunnError {
+    /// Returns the details of a failed API call, if the error is one.
+    pub fn api_failure(&self) -> Option<&ApiFailure> {
+        match self {
+            PorkbunnError::Unauthorized(failure)
+            | PorkbunnError::DomainNotOptedIn(failure)
+            | PorkbunnError::RateLimited(failure)
+            | PorkbunnError::NotFound(failure)
+            | PorkbunnError::InvalidRequest(failure)
+            | PorkbunnError::APIResponseError(failure) => Some(failure),
+            _ => None,
+        }
+    }
+
+    /// Whether the same request may succeed when sent again later: rate limits,
+    /// server errors, timeouts and connection failures.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            PorkbunnError::RateLimited(_) => true,
+            PorkbunnError::ReqwestError(err) => {
+                err.is_connect() || err.is_timeout() || err.is_request() || err.is_body()
+            }
+            err => err
+                .api_failure()
+                .is_some_and(|failure| failure.http_status >= 500),
+        }
+    }
+
+    /// Whether the API credentials were rejected.
+    pub fn is_auth(&self) -> bool {
+        matches!(self, PorkbunnError::Unauthorized(_))
+    }
+
+    /// Whether the requested object does not exist.
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            PorkbunnError::NotFound(_)
+                | PorkbunnError::RecordNotFound { .. }
+                | PorkbunnError::ZoneNotFound(_)
+        )
+    }
+
+    /// Returns how long to wait before retrying, if the server asked for a delay.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.api_failure().and_then(|failure| failure.retry_after)
+    }
 }
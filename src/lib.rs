@@ -1,5 +1,18 @@
+#[cfg(not(any(feature = "native-tls", feature = "rustls-tls")))]
+compile_error!(
+    "porkbun-rs needs a TLS backend, enable either the `native-tls` or the `rustls-tls` feature"
+);
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
 pub mod cli;
 pub mod client;
+pub mod dns_record;
 pub mod errors;
 pub mod porkbunn_client;
+pub mod provider;
+pub mod rate_limit;
+pub mod redact;
 pub mod serde_ext;
+#[cfg(feature = "testing")]
+pub mod testing;
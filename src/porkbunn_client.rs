@@ -1,9 +1,20 @@
-use crate::client::HTTPClient;
+use crate::client::{Call, Endpoint, HTTPClient, RetryPolicy};
+use crate::dns_record::DnsRecord;
+pub use crate::dns_record::RecordUpdate;
 use crate::errors::PorkbunnError;
-use crate::{make_json_request, make_request};
+use crate::rate_limit::{EndpointClass, RateLimit, RateLimiter};
+use crate::redact::REDACTED;
+use crate::serde_ext::{from_str_or_number, map_or_empty_seq, yes_no};
+use chrono::NaiveDateTime;
+use futures_util::{Stream, TryStreamExt, stream};
 use reqwest::header::HeaderValue;
+use secrecy::zeroize::Zeroize;
+use secrecy::{ExposeSecret, SecretString};
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +23,43 @@ pub struct ResponseListDomains {
     pub domains: Vec<Domain>,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsePing {
+    pub status: String,
+    pub your_ip: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseApiKeyRequest {
+    pub status: String,
+    /// Token to pass to `retrieve_api_key` once the request has been approved.
+    pub request_token: String,
+    /// Page on which the account owner approves the request.
+    pub auth_url: String,
+}
+
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseApiKeyRetrieve {
+    pub status: String,
+    #[serde(rename = "apikey")]
+    pub api_key: String,
+    #[serde(rename = "secretapikey")]
+    pub secret_api_key: String,
+}
+
+impl std::fmt::Debug for ResponseApiKeyRetrieve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseApiKeyRetrieve")
+            .field("status", &self.status)
+            .field("api_key", &REDACTED)
+            .field("secret_api_key", &REDACTED)
+            .finish()
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseCreateRecord {
@@ -25,6 +73,228 @@ pub struct ResponseDeleteRecord {
     pub status: String,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseEditRecord {
+    pub status: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseGetNameservers {
+    pub status: String,
+    pub ns: Vec<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseUpdateNameservers {
+    pub status: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseAddUrlForward {
+    pub status: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseListUrlForwards {
+    pub status: String,
+    pub forwards: Vec<UrlForwardRecord>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseDeleteUrlForward {
+    pub status: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseCreateGlue {
+    pub status: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseUpdateGlue {
+    pub status: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseDeleteGlue {
+    pub status: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseGetGlue {
+    pub status: String,
+    /// Pairs of fully qualified glue host names and their addresses.
+    #[serde(default)]
+    pub hosts: Vec<(String, GlueAddresses)>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlueAddresses {
+    #[serde(default)]
+    pub v4: Vec<Ipv4Addr>,
+    #[serde(default)]
+    pub v6: Vec<Ipv6Addr>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseCreateDsRecord {
+    pub status: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseGetDsRecords {
+    pub status: String,
+    /// DS records keyed by their key tag.
+    #[serde(default, deserialize_with = "map_or_empty_seq")]
+    pub records: BTreeMap<String, DsRecord>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseDeleteDsRecord {
+    pub status: String,
+}
+
+/// The certificate bundle Porkbun issued for a domain, all PEM encoded.
+///
+/// The private key is masked in the `Debug` output.
+#[derive(Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseSslBundle {
+    pub status: String,
+    /// The leaf certificate followed by its intermediates.
+    #[serde(rename = "certificatechain")]
+    pub certificate_chain: String,
+    #[serde(rename = "privatekey")]
+    pub private_key: String,
+    #[serde(rename = "publickey")]
+    pub public_key: String,
+}
+
+impl std::fmt::Debug for ResponseSslBundle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseSslBundle")
+            .field("status", &self.status)
+            .field("certificate_chain", &self.certificate_chain)
+            .field("private_key", &REDACTED)
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseCheckDomain {
+    pub status: String,
+    pub response: DomainAvailability,
+    pub limits: Option<RateLimits>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseRegisterDomain {
+    pub status: String,
+    pub domain: Option<String>,
+    /// Amount charged in cents.
+    #[serde(default, deserialize_with = "from_str_or_number")]
+    pub cost: u64,
+    #[serde(default, deserialize_with = "from_str_or_number")]
+    pub order_id: u64,
+    /// Account balance in cents after the purchase.
+    #[serde(default, deserialize_with = "from_str_or_number")]
+    pub balance: u64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseUpdateAutoRenew {
+    pub status: String,
+    /// Outcome per domain.
+    #[serde(default, deserialize_with = "map_or_empty_seq")]
+    pub results: BTreeMap<String, AutoRenewResult>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoRenewResult {
+    pub status: String,
+    pub message: Option<String>,
+}
+
+/// Availability and price quote of a domain.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DomainAvailability {
+    #[serde(with = "yes_no")]
+    pub avail: bool,
+    /// The operation the price applies to, e.g. `registration`.
+    #[serde(rename = "type")]
+    pub type_field: String,
+    /// Price in USD for the first year.
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub price: f64,
+    #[serde(default, with = "yes_no")]
+    pub first_year_promo: bool,
+    /// Price in USD after the first year.
+    #[serde(default, deserialize_with = "from_str_or_number")]
+    pub regular_price: f64,
+    #[serde(default, with = "yes_no")]
+    pub premium: bool,
+    /// Quotes for further operations keyed by operation, e.g. `renewal`.
+    #[serde(default, deserialize_with = "map_or_empty_seq")]
+    pub additional: BTreeMap<String, PriceQuote>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceQuote {
+    #[serde(rename = "type")]
+    pub type_field: String,
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub price: f64,
+    #[serde(default, deserialize_with = "from_str_or_number")]
+    pub regular_price: f64,
+}
+
+/// Rate limit state reported by rate limited endpoints.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimits {
+    /// Length of the rate limit window in seconds.
+    #[serde(rename = "TTL", deserialize_with = "from_str_or_number")]
+    pub ttl: u64,
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub limit: u32,
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub used: u32,
+    pub natural_language: Option<String>,
+}
+
+impl RateLimits {
+    /// Returns how long to wait before the next call, which is the length of the
+    /// window once every call in it has been used up.
+    pub fn wait(&self) -> Duration {
+        if self.used >= self.limit {
+            Duration::from_secs(self.ttl)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseListDnsRecords {
@@ -45,6 +315,44 @@ pub struct Record {
     pub notes: Option<String>,
 }
 
+/// HTTP status used when redirecting a URL forward.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwardType {
+    /// Redirects with a 302.
+    #[default]
+    Temporary,
+    /// Redirects with a 301.
+    Permanent,
+}
+
+/// A URL forward of a domain, as sent to the `domain/addUrlForward` endpoint.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlForward {
+    /// Subdomain to forward, without the domain itself. Empty for the root domain.
+    pub subdomain: String,
+    /// Where to forward to, e.g. `https://example.net`.
+    pub location: String,
+    #[serde(rename = "type")]
+    pub forward_type: ForwardType,
+    /// Append the requested path to `location`.
+    #[serde(with = "yes_no")]
+    pub include_path: bool,
+    /// Also forward all subdomains of `subdomain`.
+    #[serde(with = "yes_no")]
+    pub wildcard: bool,
+}
+
+/// An existing URL forward as returned by the `domain/getUrlForwarding` endpoint.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlForwardRecord {
+    pub id: String,
+    #[serde(flatten)]
+    pub forward: UrlForward,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Domain {
@@ -57,17 +365,406 @@ pub struct Domain {
     pub tld: String,
     pub whois_privacy: u32,
     pub security_lock: u32,
+    /// Only filled in when the domains are listed with labels.
+    #[serde(default)]
+    pub labels: Vec<Label>,
+}
+
+impl Domain {
+    /// Whether the domain renews automatically.
+    pub fn auto_renews(&self) -> bool {
+        self.auto_renew == "1"
+    }
+
+    /// Parses `expire_date`, which the API reports in UTC as `YYYY-MM-DD HH:MM:SS`.
+    pub fn expires_at(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.expire_date, "%Y-%m-%d %H:%M:%S").ok()
+    }
+}
+
+/// A label attached to a domain in the Porkbun dashboard.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Label {
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub id: u64,
+    pub title: String,
+    pub color: String,
+}
+
+/// Builds the url for the `dns/*ByNameType` endpoints, which leave out the
+/// subdomain segment for records on the root domain.
+fn name_type_url(action: &str, domain: &str, record_type: &str, name: &str) -> String {
+    if name.is_empty() {
+        format!("dns/{}/{}/{}", action, domain, record_type)
+    } else {
+        format!("dns/{}/{}/{}/{}", action, domain, record_type, name)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsePricing {
+    pub status: String,
+    /// Pricing keyed by TLD without the leading dot, e.g. `com`.
+    pub pricing: BTreeMap<String, TldPricing>,
+}
+
+/// Prices in USD for a single TLD.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TldPricing {
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub registration: f64,
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub renewal: f64,
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub transfer: f64,
+    /// Coupons keyed by the operation they apply to, e.g. `registration`.
+    #[serde(default, deserialize_with = "map_or_empty_seq")]
+    pub coupons: BTreeMap<String, Coupon>,
+}
+
+impl TldPricing {
+    /// Returns how many times more expensive renewing is compared to registering.
+    pub fn renewal_markup(&self) -> f64 {
+        if self.registration > 0.0 {
+            self.renewal / self.registration
+        } else {
+            f64::INFINITY
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Coupon {
+    pub code: String,
+    pub max_per_user: Option<u32>,
+    pub first_year_only: Option<String>,
+    #[serde(rename = "type")]
+    pub type_field: Option<String>,
+    pub amount: Option<f64>,
+}
+
+/// A DNSSEC delegation signer record published at the registry.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DsRecord {
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub key_tag: u16,
+    /// IANA DNSSEC algorithm number, e.g. `13` for ECDSAP256SHA256.
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub alg: u8,
+    /// IANA DS digest type, e.g. `2` for SHA-256.
+    #[serde(deserialize_with = "from_str_or_number")]
+    pub digest_type: u8,
+    /// Hex encoded digest of the DNSKEY.
+    pub digest: String,
+}
+
+impl DsRecord {
+    /// Returns the IANA mnemonic of a DNSSEC algorithm number, or `None` if the
+    /// number is not assigned to a signing algorithm.
+    pub fn algorithm_name(alg: u8) -> Option<&'static str> {
+        match alg {
+            1 => Some("RSAMD5"),
+            3 => Some("DSA"),
+            5 => Some("RSASHA1"),
+            6 => Some("DSA-NSEC3-SHA1"),
+            7 => Some("RSASHA1-NSEC3-SHA1"),
+            8 => Some("RSASHA256"),
+            10 => Some("RSASHA512"),
+            12 => Some("ECC-GOST"),
+            13 => Some("ECDSAP256SHA256"),
+            14 => Some("ECDSAP384SHA384"),
+            15 => Some("ED25519"),
+            16 => Some("ED448"),
+            17 => Some("SM2SM3"),
+            23 => Some("ECC-GOST12"),
+            _ => None,
+        }
+    }
+
+    /// Returns the length in hex characters of a digest of the given IANA
+    /// digest type, or `None` if the digest type is not assigned.
+    pub fn digest_length(digest_type: u8) -> Option<usize> {
+        match digest_type {
+            1 => Some(40),
+            2 | 3 | 5 | 6 => Some(64),
+            4 => Some(96),
+            _ => None,
+        }
+    }
+
+    /// Checks the algorithm and digest type against the IANA registries and the
+    /// digest against the length its digest type requires.
+    pub fn validate(&self) -> Result<(), PorkbunnError> {
+        if DsRecord::algorithm_name(self.alg).is_none() {
+            return Err(PorkbunnError::ValidationError(format!(
+                "{} is not an IANA DNSSEC algorithm number",
+                self.alg
+            )));
+        }
+        let Some(length) = DsRecord::digest_length(self.digest_type) else {
+            return Err(PorkbunnError::ValidationError(format!(
+                "{} is not an IANA DS digest type",
+                self.digest_type
+            )));
+        };
+        if self.digest.len() != length || !self.digest.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(PorkbunnError::ValidationError(format!(
+                "digest type {} needs a digest of {} hex characters",
+                self.digest_type, length
+            )));
+        }
+        Ok(())
+    }
+
+    /// Formats the record in DNS presentation format, e.g.
+    /// `example.com. IN DS 2371 13 2 1F987CC6...`.
+    pub fn presentation(&self, domain: &str) -> String {
+        format!(
+            "{}. IN DS {} {} {} {}",
+            domain.trim_end_matches('.'),
+            self.key_tag,
+            self.alg,
+            self.digest_type,
+            self.digest.to_uppercase()
+        )
+    }
+}
+
+/// Checks the arguments shared by the glue record endpoints before sending them.
+fn validate_glue(subdomain: &str, ips: &[IpAddr]) -> Result<(), PorkbunnError> {
+    if subdomain.is_empty() || subdomain.contains('/') {
+        return Err(PorkbunnError::ValidationError(format!(
+            "invalid glue host {:?}",
+            subdomain
+        )));
+    }
+    if ips.is_empty() {
+        return Err(PorkbunnError::ValidationError(
+            "a glue record needs at least one IP address".to_string(),
+        ));
+    }
+    if ips
+        .iter()
+        .any(|ip| ip.is_unspecified() || ip.is_loopback() || ip.is_multicast())
+    {
+        return Err(PorkbunnError::ValidationError(format!(
+            "glue records need routable IP addresses, got {:?}",
+            ips
+        )));
+    }
+    Ok(())
 }
 
 /// The `PorkbunnClient` struct represents a client for interacting with the Porkbun API.
+///
+/// Cloning the client is cheap; clones share the connection pool and rate limits.
+/// The credentials are zeroed when the client is dropped and masked in its
+/// `Debug` output.
+#[derive(Clone)]
 pub struct PorkbunnClient {
     http_client: HTTPClient,
-    api_key: String,
-    api_secret: String,
+    api_key: SecretString,
+    api_secret: SecretString,
+}
+
+impl std::fmt::Debug for PorkbunnClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PorkbunnClient")
+            .field("http_client", &self.http_client)
+            .field("api_key", &REDACTED)
+            .field("api_secret", &REDACTED)
+            .finish()
+    }
+}
+
+/// Base URL of the Porkbun API.
+pub const DEFAULT_BASE_URL: &str = "https://api.porkbun.com/api/json/";
+
+/// Version of the Porkbun API this crate is written against.
+pub const DEFAULT_VERSION: &str = "v3";
+
+/// Builder for a `PorkbunnClient` with a customised HTTP client.
+///
+/// ```rust, no_run
+/// use porkbun_rs::porkbunn_client::PorkbunnClient;
+/// use std::time::Duration;
+///
+/// # fn run() -> Result<(), porkbun_rs::errors::PorkbunnError> {
+/// let client = PorkbunnClient::builder()
+///     .credentials("pk1_...", "sk1_...")
+///     .timeout(Duration::from_secs(30))
+///     .proxy("socks5://127.0.0.1:1080")
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct PorkbunnClientBuilder {
+    base_url: Option<String>,
+    version: Option<String>,
+    api_key: SecretString,
+    api_secret: SecretString,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    proxy: Option<String>,
+    user_agent: Option<String>,
+    root_certificates: Vec<Vec<u8>>,
+    http_client: Option<reqwest::Client>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limits: Vec<(EndpointClass, RateLimit)>,
+}
+
+impl PorkbunnClientBuilder {
+    /// Sets the base URL of the API, defaults to `DEFAULT_BASE_URL`.
+    pub fn base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sets the version of the API, defaults to `DEFAULT_VERSION`.
+    pub fn version<S: Into<String>>(mut self, version: S) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Sets the API key and secret used to authenticate requests.
+    pub fn credentials<S: Into<SecretString>, T: Into<SecretString>>(
+        mut self,
+        api_key: S,
+        api_secret: T,
+    ) -> Self {
+        self.api_key = api_key.into();
+        self.api_secret = api_secret.into();
+        self
+    }
+
+    /// Sets the timeout for a whole request, from connecting until the response body has been read.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sends all requests through a proxy, e.g. `http://proxy:3128` or `socks5://127.0.0.1:1080`.
+    pub fn proxy<S: Into<String>>(mut self, proxy_url: S) -> Self {
+        self.proxy = Some(proxy_url.into());
+        self
+    }
+
+    /// Sets the `User-Agent` header, defaults to `porkbun-rs/<version>`.
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Trusts the PEM encoded certificates in `pem` in addition to the system
+    /// roots, e.g. the CA of a TLS intercepting proxy.
+    pub fn add_root_certificate_pem<B: Into<Vec<u8>>>(mut self, pem: B) -> Self {
+        self.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Uses a caller-supplied HTTP client as is. It cannot be combined with the
+    /// other HTTP options of this builder.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Sets how transient failures are retried, defaults to `RetryPolicy::default()`.
+    /// Use `RetryPolicy::none()` to disable retries.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Throttles requests to endpoints of `class` on the client side, e.g. to
+    /// one `checkDomain` call every 10 seconds. The limit is shared by all clones
+    /// of the built client. Endpoints without a limit are not throttled.
+    pub fn rate_limit(mut self, class: EndpointClass, limit: RateLimit) -> Self {
+        self.rate_limits.retain(|(c, _)| *c != class);
+        self.rate_limits.push((class, limit));
+        self
+    }
+
+    /// Builds the `PorkbunnClient`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the client, or an error of type `PorkbunnError` if the
+    /// base URL, proxy, certificates or rate limits are invalid or the HTTP client
+    /// cannot be built.
+    pub fn build(self) -> Result<PorkbunnClient, PorkbunnError> {
+        let client = match self.http_client {
+            Some(client) => {
+                if self.timeout.is_some()
+                    || self.connect_timeout.is_some()
+                    || self.proxy.is_some()
+                    || self.user_agent.is_some()
+                    || !self.root_certificates.is_empty()
+                {
+                    return Err(PorkbunnError::ValidationError(
+                        "HTTP options cannot be combined with a caller-supplied client".to_string(),
+                    ));
+                }
+                client
+            }
+            None => {
+                // Create headers with content-type set to application/json
+                let mut headers = reqwest::header::HeaderMap::new();
+                headers.insert("content-type", HeaderValue::from_static("application/json"));
+
+                let user_agent = self
+                    .user_agent
+                    .unwrap_or_else(|| format!("porkbun-rs/{}", env!("CARGO_PKG_VERSION")));
+                let mut builder = reqwest::ClientBuilder::new()
+                    .default_headers(headers)
+                    .user_agent(user_agent);
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(proxy) = self.proxy {
+                    builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+                }
+                // the TLS backend is missing otherwise, see the compile_error! in lib.rs
+                #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+                for pem in &self.root_certificates {
+                    for certificate in reqwest::Certificate::from_pem_bundle(pem)? {
+                        builder = builder.add_root_certificate(certificate);
+                    }
+                }
+                builder.build()?
+            }
+        };
+
+        Ok(PorkbunnClient {
+            http_client: HTTPClient::new(
+                self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL),
+                client,
+                self.version.as_deref().unwrap_or(DEFAULT_VERSION),
+            )?
+            .with_retry_policy(self.retry_policy.unwrap_or_default())
+            .with_rate_limiter(RateLimiter::new(self.rate_limits)?),
+            api_key: self.api_key,
+            api_secret: self.api_secret,
+        })
+    }
 }
 
 impl PorkbunnClient {
-    /// Creates a new `PorkbunnClient` instance.
+    /// Creates a new `PorkbunnClient` instance with a default HTTP client.
     ///
     /// # Arguments
     ///
@@ -78,64 +775,172 @@ impl PorkbunnClient {
     ///
     /// # Returns
     ///
-    /// A new `PorkbunnClient` instance.
-    fn inner_client(
+    /// A `Result` containing the new `PorkbunnClient`, or an error of type `PorkbunnError`
+    /// if the base URL is invalid.
+    pub fn new(
         base_url: &str,
         version: &str,
         api_key: &str,
         api_secret: &str,
-    ) -> PorkbunnClient {
-        // Create headers with content-type set to application/json
-        let mut headers = reqwest::header::HeaderMap::new();
-        headers.insert(
-            "content-type",
-            HeaderValue::from_str("application/json").unwrap(),
-        );
-
-        // Build the HTTP client with default headers
-        let client = reqwest::ClientBuilder::new()
-            .default_headers(headers)
+    ) -> Result<PorkbunnClient, PorkbunnError> {
+        PorkbunnClient::builder()
+            .base_url(base_url)
+            .version(version)
+            .credentials(api_key, api_secret)
             .build()
-            .unwrap();
+    }
 
-        PorkbunnClient {
-            http_client: HTTPClient::new(base_url, client, version),
-            api_key: api_key.to_string(),
-            api_secret: api_secret.to_string(),
-        }
+    /// Returns a builder to configure timeouts, proxies, TLS roots or a custom
+    /// HTTP client.
+    pub fn builder() -> PorkbunnClientBuilder {
+        PorkbunnClientBuilder::default()
     }
 
-    /// Creates a new `PorkbunnClient` instance.
+    /// Sends a request to an endpoint and returns its typed response.
+    ///
+    /// All methods of the client go through here; use it directly together with
+    /// `Call` or an own `Endpoint` implementation to reach endpoints the crate
+    /// does not cover yet.
     ///
     /// # Arguments
     ///
-    /// * `base_url` - The base URL of the Porkbun API.
-    /// * `version` - The version of the Porkbun API.
-    /// * `api_key` - The API key for authentication.
-    /// * `api_secret` - The API secret for authentication.
+    /// * `endpoint` - The endpoint to call.
     ///
     /// # Returns
     ///
-    /// A new `PorkbunnClient` instance.
-    pub fn new(base_url: &str, version: &str, api_key: &str, api_secret: &str) -> PorkbunnClient {
-        PorkbunnClient::inner_client(base_url, version, api_key, api_secret)
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn execute<E: Endpoint>(&self, endpoint: &E) -> Result<E::Response, PorkbunnError> {
+        let mut body = match serde_json::to_value(endpoint.body())? {
+            serde_json::Value::Object(body) => body,
+            serde_json::Value::Null => serde_json::Map::new(),
+            _ => {
+                return Err(PorkbunnError::ValidationError(format!(
+                    "the body of {} is not a JSON object",
+                    endpoint.path()
+                )));
+            }
+        };
+        if endpoint.authenticated() {
+            body.insert("apikey".to_string(), self.api_key.expose_secret().into());
+            body.insert(
+                "secretapikey".to_string(),
+                self.api_secret.expose_secret().into(),
+            );
+        }
+        let mut body = serde_json::Value::Object(body);
+        let response = self
+            .http_client
+            .post_json(&endpoint.path(), &body, endpoint.idempotent())
+            .await;
+        // don't leave copies of the credentials behind in freed memory
+        for field in ["apikey", "secretapikey"] {
+            if let Some(serde_json::Value::String(secret)) = body.get_mut(field) {
+                secret.zeroize();
+            }
+        }
+        response
     }
 
-    /// Retrieves a list of DNS records for a given name.
-    ///
-    /// # Arguments
+    /// Starts the flow to provision a new API key, which the account owner has to
+    /// approve in the browser.
     ///
-    /// * `name` - The name of the DNS records to retrieve.
+    /// This endpoint does not require valid API credentials.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the response data or an error of type `PorkbunnError`.
-    pub async fn list_dns_records(
-        &self,
-        name: &str,
-    ) -> Result<ResponseListDnsRecords, PorkbunnError> {
-        let url = &format!("dns/retrieve/{}", name);
-        make_request!(self, reqwest::Method::POST, url)
+    /// A `Result` containing the approval URL and request token, or an error of type `PorkbunnError`.
+    pub async fn request_api_key(&self) -> Result<ResponseApiKeyRequest, PorkbunnError> {
+        let url = "apikey/request";
+        self.execute(&Call::empty(url).unauthenticated()).await
+    }
+
+    /// Retrieves the API key of an approved key request.
+    ///
+    /// This endpoint does not require valid API credentials.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_token` - The token returned by `request_api_key`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the new credentials, or an error of type `PorkbunnError` while
+    /// the request has not been approved yet.
+    pub async fn retrieve_api_key(
+        &self,
+        request_token: &str,
+    ) -> Result<ResponseApiKeyRetrieve, PorkbunnError> {
+        let url = "apikey/retrieve";
+        let payload = serde_json::json!({
+            "requestToken": request_token,
+        });
+        self.execute(&Call::new(url, payload).unauthenticated().idempotent())
+            .await
+    }
+
+    /// Checks that the API credentials are valid.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the public IP address of the caller as seen by Porkbun,
+    /// or an error of type `PorkbunnError` if the credentials are rejected.
+    pub async fn ping(&self) -> Result<ResponsePing, PorkbunnError> {
+        let url = "ping";
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Retrieves the registration, renewal and transfer prices of all supported TLDs.
+    ///
+    /// This endpoint does not require valid API credentials.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn get_pricing(&self) -> Result<ResponsePricing, PorkbunnError> {
+        let url = "pricing/get";
+        self.execute(&Call::empty(url).unauthenticated().idempotent())
+            .await
+    }
+
+    /// Retrieves a list of DNS records for a given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the DNS records to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn list_dns_records(
+        &self,
+        name: &str,
+    ) -> Result<ResponseListDnsRecords, PorkbunnError> {
+        let url = &format!("dns/retrieve/{}", name);
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Retrieves a single DNS record by its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the DNS record belongs to.
+    /// * `id` - The ID of the DNS record to retrieve.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the record, or `PorkbunnError::RecordNotFound` if no record
+    /// with this ID exists.
+    pub async fn get_dns_record(&self, domain: &str, id: u64) -> Result<Record, PorkbunnError> {
+        let url = &format!("dns/retrieve/{}/{}", domain, id);
+        let response: ResponseListDnsRecords = self.execute(&Call::empty(url).idempotent()).await?;
+        response
+            .records
+            .into_iter()
+            .next()
+            .ok_or_else(|| PorkbunnError::RecordNotFound {
+                domain: domain.to_string(),
+                id,
+            })
     }
 
     /// Creates a new DNS record.
@@ -144,8 +949,7 @@ impl PorkbunnClient {
     ///
     /// * `domain` - The domain for which to create the DNS record.
     /// * `name` - The name of the DNS record.
-    /// * `record_type` - The type of the DNS record.
-    /// * `ip_address` - The IP address associated with the DNS record.
+    /// * `record` - The type and content of the DNS record.
     /// * `ttl` - The time-to-live value for the DNS record.
     ///
     /// # Returns
@@ -155,20 +959,120 @@ impl PorkbunnClient {
         &self,
         domain: &str,
         name: &str,
-        record_type: &str,
-        ip_address: &str,
+        record: &DnsRecord,
         ttl: u32,
     ) -> Result<ResponseCreateRecord, PorkbunnError> {
         let url = &format!("dns/create/{}", domain);
-        let payload = &serde_json::json!({
-            "apikey": self.api_key,
-            "secretapikey": self.api_secret,
+        let payload = serde_json::json!({
             "name": name,
-            "type": record_type,
-            "content": ip_address,
+            "type": record.type_name(),
+            "content": record.content(),
             "ttl": ttl,
+            "prio": record.prio(),
+        });
+        self.execute(&Call::new(url, payload)).await
+    }
+
+    /// Edits an existing DNS record in place, keeping its ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the DNS record belongs to.
+    /// * `id` - The ID of the DNS record to edit.
+    /// * `update` - The new state of the DNS record.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn edit_dns_record(
+        &self,
+        domain: &str,
+        id: u64,
+        update: &RecordUpdate,
+    ) -> Result<ResponseEditRecord, PorkbunnError> {
+        let url = &format!("dns/edit/{}/{}", domain, id);
+        let payload = serde_json::json!({
+            "name": update.name,
+            "type": update.record.type_name(),
+            "content": update.record.content(),
+            "ttl": update.ttl,
+            "prio": update.record.prio(),
+            "notes": update.notes,
         });
-        make_json_request!(self, reqwest::Method::POST, url, payload)
+        self.execute(&Call::new(url, payload).idempotent()).await
+    }
+
+    /// Edits all DNS records matching a subdomain and record type.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the DNS records belong to.
+    /// * `update` - The new state of the DNS records. Its `name` and `record_type`
+    ///   select which records are edited.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn edit_dns_records_by_name_type(
+        &self,
+        domain: &str,
+        update: &RecordUpdate,
+    ) -> Result<ResponseEditRecord, PorkbunnError> {
+        let url = &name_type_url(
+            "editByNameType",
+            domain,
+            &update.record.type_name(),
+            &update.name,
+        );
+        let payload = serde_json::json!({
+            "content": update.record.content(),
+            "ttl": update.ttl,
+            "prio": update.record.prio(),
+            "notes": update.notes,
+        });
+        self.execute(&Call::new(url, payload).idempotent()).await
+    }
+
+    /// Retrieves all DNS records matching a subdomain and record type.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the DNS records belong to.
+    /// * `record_type` - The type of the DNS records, e.g. `A`.
+    /// * `name` - The subdomain of the DNS records. Empty for the root domain.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn retrieve_dns_records_by_name_type(
+        &self,
+        domain: &str,
+        record_type: &str,
+        name: &str,
+    ) -> Result<ResponseListDnsRecords, PorkbunnError> {
+        let url = &name_type_url("retrieveByNameType", domain, record_type, name);
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Deletes all DNS records matching a subdomain and record type.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the DNS records belong to.
+    /// * `record_type` - The type of the DNS records, e.g. `A`.
+    /// * `name` - The subdomain of the DNS records. Empty for the root domain.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn delete_dns_records_by_name_type(
+        &self,
+        domain: &str,
+        record_type: &str,
+        name: &str,
+    ) -> Result<ResponseDeleteRecord, PorkbunnError> {
+        let url = &name_type_url("deleteByNameType", domain, record_type, name);
+        self.execute(&Call::empty(url)).await
     }
 
     /// Deletes a DNS record.
@@ -187,16 +1091,423 @@ impl PorkbunnClient {
         id: u64,
     ) -> Result<ResponseDeleteRecord, PorkbunnError> {
         let url = &format!("dns/delete/{}/{}", domain, id);
-        make_request!(self, reqwest::Method::POST, url)
+        self.execute(&Call::empty(url)).await
     }
 
-    /// Retrieves a list of all domains.
+    /// Retrieves the authoritative nameservers of a domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to retrieve the nameservers.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn get_nameservers(
+        &self,
+        domain: &str,
+    ) -> Result<ResponseGetNameservers, PorkbunnError> {
+        let url = &format!("domain/getNs/{}", domain);
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Replaces the authoritative nameservers of a domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to update the nameservers.
+    /// * `nameservers` - The complete new set of nameservers, e.g. `ns1.example.net`.
     ///
     /// # Returns
     ///
     /// A `Result` containing the response data or an error of type `PorkbunnError`.
-    pub async fn list_domains(&self) -> Result<ResponseListDomains, PorkbunnError> {
+    pub async fn update_nameservers(
+        &self,
+        domain: &str,
+        nameservers: &[String],
+    ) -> Result<ResponseUpdateNameservers, PorkbunnError> {
+        let url = &format!("domain/updateNs/{}", domain);
+        let payload = serde_json::json!({
+            "ns": nameservers,
+        });
+        self.execute(&Call::new(url, payload).idempotent()).await
+    }
+
+    /// Adds a URL forward to a domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to add the URL forward.
+    /// * `forward` - The URL forward to add.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn add_url_forward(
+        &self,
+        domain: &str,
+        forward: &UrlForward,
+    ) -> Result<ResponseAddUrlForward, PorkbunnError> {
+        let url = &format!("domain/addUrlForward/{}", domain);
+        let payload = serde_json::json!({
+            "subdomain": forward.subdomain,
+            "location": forward.location,
+            "type": forward.forward_type,
+            "includePath": yes_no::as_str(forward.include_path),
+            "wildcard": yes_no::as_str(forward.wildcard),
+        });
+        self.execute(&Call::new(url, payload)).await
+    }
+
+    /// Retrieves all URL forwards of a domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to retrieve the URL forwards.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn list_url_forwards(
+        &self,
+        domain: &str,
+    ) -> Result<ResponseListUrlForwards, PorkbunnError> {
+        let url = &format!("domain/getUrlForwarding/{}", domain);
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Deletes a URL forward.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to delete the URL forward.
+    /// * `id` - The ID of the URL forward to delete.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn delete_url_forward(
+        &self,
+        domain: &str,
+        id: u64,
+    ) -> Result<ResponseDeleteUrlForward, PorkbunnError> {
+        let url = &format!("domain/deleteUrlForward/{}/{}", domain, id);
+        self.execute(&Call::empty(url)).await
+    }
+
+    /// Creates a glue record for a nameserver host under a domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the nameserver host belongs to.
+    /// * `subdomain` - The nameserver host without the domain itself, e.g. `ns1`.
+    /// * `ips` - The IPv4 and IPv6 addresses of the nameserver host.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn create_glue(
+        &self,
+        domain: &str,
+        subdomain: &str,
+        ips: &[IpAddr],
+    ) -> Result<ResponseCreateGlue, PorkbunnError> {
+        validate_glue(subdomain, ips)?;
+        let url = &format!("domain/createGlue/{}/{}", domain, subdomain);
+        let payload = serde_json::json!({
+            "ips": ips,
+        });
+        self.execute(&Call::new(url, payload)).await
+    }
+
+    /// Replaces the addresses of an existing glue record.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the nameserver host belongs to.
+    /// * `subdomain` - The nameserver host without the domain itself, e.g. `ns1`.
+    /// * `ips` - The complete new set of IPv4 and IPv6 addresses.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn update_glue(
+        &self,
+        domain: &str,
+        subdomain: &str,
+        ips: &[IpAddr],
+    ) -> Result<ResponseUpdateGlue, PorkbunnError> {
+        validate_glue(subdomain, ips)?;
+        let url = &format!("domain/updateGlue/{}/{}", domain, subdomain);
+        let payload = serde_json::json!({
+            "ips": ips,
+        });
+        self.execute(&Call::new(url, payload).idempotent()).await
+    }
+
+    /// Deletes a glue record.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain the nameserver host belongs to.
+    /// * `subdomain` - The nameserver host without the domain itself, e.g. `ns1`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn delete_glue(
+        &self,
+        domain: &str,
+        subdomain: &str,
+    ) -> Result<ResponseDeleteGlue, PorkbunnError> {
+        let url = &format!("domain/deleteGlue/{}/{}", domain, subdomain);
+        self.execute(&Call::empty(url)).await
+    }
+
+    /// Retrieves all glue records of a domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to retrieve the glue records.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn get_glue(&self, domain: &str) -> Result<ResponseGetGlue, PorkbunnError> {
+        let url = &format!("domain/getGlue/{}", domain);
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Publishes a DS record for a domain at the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to create the DS record.
+    /// * `record` - The DS record to create. It is validated before sending.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn create_ds_record(
+        &self,
+        domain: &str,
+        record: &DsRecord,
+    ) -> Result<ResponseCreateDsRecord, PorkbunnError> {
+        record.validate()?;
+        let url = &format!("dnssec/createRecord/{}", domain);
+        let payload = serde_json::json!({
+            "keyTag": record.key_tag.to_string(),
+            "alg": record.alg.to_string(),
+            "digestType": record.digest_type.to_string(),
+            "digest": record.digest,
+        });
+        self.execute(&Call::new(url, payload)).await
+    }
+
+    /// Retrieves the DS records of a domain published at the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to retrieve the DS records.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn get_ds_records(
+        &self,
+        domain: &str,
+    ) -> Result<ResponseGetDsRecords, PorkbunnError> {
+        let url = &format!("dnssec/getRecords/{}", domain);
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Deletes a DS record of a domain at the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to delete the DS record.
+    /// * `key_tag` - The key tag of the DS record to delete.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn delete_ds_record(
+        &self,
+        domain: &str,
+        key_tag: u16,
+    ) -> Result<ResponseDeleteDsRecord, PorkbunnError> {
+        let url = &format!("dnssec/deleteRecord/{}/{}", domain, key_tag);
+        self.execute(&Call::empty(url)).await
+    }
+
+    /// Retrieves the free SSL certificate bundle Porkbun issued for a domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain for which to retrieve the certificate bundle.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn retrieve_ssl_bundle(
+        &self,
+        domain: &str,
+    ) -> Result<ResponseSslBundle, PorkbunnError> {
+        let url = &format!("ssl/retrieve/{}", domain);
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Checks whether a domain is available for registration and quotes its price.
+    ///
+    /// This endpoint is strictly rate limited; the returned `limits` tell how long
+    /// to wait before the next check.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain to check, e.g. `example.com`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn check_domain(&self, domain: &str) -> Result<ResponseCheckDomain, PorkbunnError> {
+        let url = &format!("domain/checkDomain/{}", domain);
+        self.execute(&Call::empty(url).idempotent()).await
+    }
+
+    /// Registers a domain for one year, paying from the account balance.
+    ///
+    /// Calling this agrees to Porkbun's terms of service for the registration.
+    ///
+    /// # Arguments
+    ///
+    /// * `domain` - The domain to register, e.g. `example.com`.
+    /// * `cost` - The quoted price in cents, as returned by `check_domain`. The
+    ///   registration is refused if it does not match the actual price.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn register_domain(
+        &self,
+        domain: &str,
+        cost: u64,
+    ) -> Result<ResponseRegisterDomain, PorkbunnError> {
+        let url = &format!("domain/create/{}", domain);
+        let payload = serde_json::json!({
+            "cost": cost,
+            "agreeToTerms": "yes",
+        });
+        self.execute(&Call::new(url, payload)).await
+    }
+
+    /// Turns auto-renewal on or off for one or more domains.
+    ///
+    /// # Arguments
+    ///
+    /// * `domains` - The domains to update.
+    /// * `enabled` - Whether the domains should renew automatically.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the outcome per domain or an error of type `PorkbunnError`.
+    pub async fn update_auto_renew(
+        &self,
+        domains: &[String],
+        enabled: bool,
+    ) -> Result<ResponseUpdateAutoRenew, PorkbunnError> {
+        let Some(first) = domains.first() else {
+            return Err(PorkbunnError::ValidationError(
+                "no domains to update".to_string(),
+            ));
+        };
+        // the domain in the url is ignored once `domains` is sent
+        let url = &format!("domain/updateAutoRenew/{}", first);
+        let payload = serde_json::json!({
+            "status": if enabled { "on" } else { "off" },
+            "domains": domains,
+        });
+        self.execute(&Call::new(url, payload).idempotent()).await
+    }
+
+    /// Retrieves a single page of domains.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The index of the first domain to return. Pages hold up to 1000 domains.
+    /// * `include_labels` - Whether to fill in the labels of each domain.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn list_domains_page(
+        &self,
+        start: u64,
+        include_labels: bool,
+    ) -> Result<ResponseListDomains, PorkbunnError> {
         let url = "domain/listAll";
-        make_request!(self, reqwest::Method::POST, url)
+        let payload = serde_json::json!({
+            "start": start.to_string(),
+            "includeLabels": yes_no::as_str(include_labels),
+        });
+        self.execute(&Call::new(url, payload).idempotent()).await
+    }
+
+    /// Retrieves a list of all domains, following the pages until the last one.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_labels` - Whether to fill in the labels of each domain.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the response data or an error of type `PorkbunnError`.
+    pub async fn list_domains(
+        &self,
+        include_labels: bool,
+    ) -> Result<ResponseListDomains, PorkbunnError> {
+        let mut all = ResponseListDomains::default();
+        loop {
+            let page = self
+                .list_domains_page(all.domains.len() as u64, include_labels)
+                .await?;
+            all.status = page.status;
+            if page.domains.is_empty() {
+                return Ok(all);
+            }
+            all.domains.extend(page.domains);
+        }
+    }
+
+    /// Streams all domains, fetching the next page only once the previous one
+    /// has been consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `include_labels` - Whether to fill in the labels of each domain.
+    ///
+    /// # Returns
+    ///
+    /// A `Stream` of domains or errors of type `PorkbunnError`. The stream ends
+    /// after the first error.
+    pub fn stream_domains(
+        &self,
+        include_labels: bool,
+    ) -> impl Stream<Item = Result<Domain, PorkbunnError>> + '_ {
+        stream::try_unfold(Some(0u64), move |start| async move {
+            let Some(start) = start else {
+                return Ok::<_, PorkbunnError>(None);
+            };
+            let domains = self.list_domains_page(start, include_labels).await?.domains;
+            let next = if domains.is_empty() {
+                None
+            } else {
+                Some(start + domains.len() as u64)
+            };
+            Ok(Some((
+                stream::iter(domains.into_iter().map(Ok::<_, PorkbunnError>)),
+                next,
+            )))
+        })
+        .try_flatten()
     }
 }
@@ -0,0 +1,385 @@
+//! A provider-agnostic interface to DNS hosting.
+//!
+//! Tools written against `DnsProvider` work with the Porkbun API through
+//! `PorkbunnClient` and can be unit-tested against `InMemoryProvider`.
+
+use crate::client::Call;
+use crate::dns_record::{DnsRecord, RecordUpdate};
+use crate::errors::PorkbunnError;
+use crate::porkbunn_client::{PorkbunnClient, Record, ResponseCreateRecord};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+/// A DNS record as stored by a provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneRecord {
+    /// ID assigned by the provider.
+    pub id: u64,
+    /// Subdomain of the record, relative to the zone. Empty for the zone apex.
+    pub name: String,
+    pub record: DnsRecord,
+    pub ttl: u32,
+    pub notes: Option<String>,
+}
+
+impl ZoneRecord {
+    fn from_record(record: &Record, zone: &str) -> Result<ZoneRecord, PorkbunnError> {
+        let invalid = |field: &str, value: &str| {
+            PorkbunnError::ValidationError(format!("invalid record {} {:?}", field, value))
+        };
+        Ok(ZoneRecord {
+            id: record.id.parse().map_err(|_| invalid("id", &record.id))?,
+            name: subdomain_of(&record.name, zone)?,
+            record: DnsRecord::try_from(record)?,
+            ttl: record
+                .ttl
+                .parse()
+                .map_err(|_| invalid("ttl", &record.ttl))?,
+            notes: record.notes.clone().filter(|notes| !notes.is_empty()),
+        })
+    }
+}
+
+/// Strips `domain` from a fully qualified record name, e.g. `www.example.com`
+/// becomes `www` and `example.com` becomes an empty string. Names are compared
+/// case-insensitively and must end at a label boundary.
+///
+/// # Returns
+///
+/// The subdomain, or a `PorkbunnError::ValidationError` if `name` is not within `domain`.
+pub(crate) fn subdomain_of(name: &str, domain: &str) -> Result<String, PorkbunnError> {
+    let name = name.trim_end_matches('.');
+    let domain = domain.trim_end_matches('.');
+    if name.eq_ignore_ascii_case(domain) {
+        return Ok(String::new());
+    }
+    match name.len().checked_sub(domain.len() + 1) {
+        Some(dot)
+            if name.as_bytes()[dot] == b'.'
+                && name.as_bytes()[dot + 1..].eq_ignore_ascii_case(domain.as_bytes()) =>
+        {
+            Ok(name[..dot].to_string())
+        }
+        _ => Err(PorkbunnError::ValidationError(format!(
+            "record {:?} is not within {}",
+            name, domain
+        ))),
+    }
+}
+
+/// Joins a subdomain and a domain into a fully qualified record name, the
+/// inverse of `subdomain_of`.
+pub(crate) fn fqdn(name: &str, domain: &str) -> String {
+    if name.is_empty() {
+        domain.to_string()
+    } else {
+        format!("{}.{}", name, domain)
+    }
+}
+
+/// Operations on the DNS zones hosted by a provider.
+///
+/// Records are addressed by zone and provider-assigned ID; names are relative
+/// to the zone. Creating and updating take a `RecordUpdate`, whose `ttl` falls
+/// back to the provider default when `None`. Each provider reports failures
+/// with its own `Error` type.
+///
+/// ```rust, no_run
+/// use porkbun_rs::dns_record::{DnsRecord, RecordUpdate};
+/// use porkbun_rs::provider::DnsProvider;
+/// use std::net::Ipv4Addr;
+///
+/// /// Points `name` at `ip`, replacing existing A records.
+/// async fn point_to<P: DnsProvider>(
+///     provider: &P,
+///     zone: &str,
+///     name: &str,
+///     ip: Ipv4Addr,
+/// ) -> Result<(), P::Error> {
+///     let update = RecordUpdate {
+///         name: name.to_string(),
+///         record: DnsRecord::A(ip),
+///         ttl: None,
+///         notes: None,
+///     };
+///     let existing: Vec<u64> = provider
+///         .list_records(zone)
+///         .await?
+///         .into_iter()
+///         .filter(|r| r.name == name && matches!(r.record, DnsRecord::A(_)))
+///         .map(|r| r.id)
+///         .collect();
+///     match existing.split_first() {
+///         Some((first, rest)) => {
+///             for id in rest {
+///                 provider.delete_record(zone, *id).await?;
+///             }
+///             provider.update_record(zone, *first, &update).await
+///         }
+///         None => provider.create_record(zone, &update).await.map(|_| ()),
+///     }
+/// }
+/// ```
+pub trait DnsProvider {
+    /// Error returned when an operation fails.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Lists the zones, i.e. domains, the provider hosts.
+    fn list_zones(&self) -> impl Future<Output = Result<Vec<String>, Self::Error>> + Send;
+
+    /// Lists all records of a zone.
+    fn list_records(
+        &self,
+        zone: &str,
+    ) -> impl Future<Output = Result<Vec<ZoneRecord>, Self::Error>> + Send;
+
+    /// Creates a record and returns its ID.
+    fn create_record(
+        &self,
+        zone: &str,
+        record: &RecordUpdate,
+    ) -> impl Future<Output = Result<u64, Self::Error>> + Send;
+
+    /// Replaces an existing record, keeping its ID.
+    fn update_record(
+        &self,
+        zone: &str,
+        id: u64,
+        record: &RecordUpdate,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Deletes a record.
+    fn delete_record(
+        &self,
+        zone: &str,
+        id: u64,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+}
+
+impl DnsProvider for PorkbunnClient {
+    type Error = PorkbunnError;
+
+    async fn list_zones(&self) -> Result<Vec<String>, PorkbunnError> {
+        let domains = self.list_domains(false).await?;
+        Ok(domains.domains.into_iter().map(|d| d.domain).collect())
+    }
+
+    /// Records of types without a typed variant, e.g. SSHFP, are returned as
+    /// `DnsRecord::Other`. Fails with a `PorkbunnError::ValidationError` if a record
+    /// of a known type is malformed, rather than returning an incomplete zone.
+    async fn list_records(&self, zone: &str) -> Result<Vec<ZoneRecord>, PorkbunnError> {
+        let response = self.list_dns_records(zone).await?;
+        response
+            .records
+            .iter()
+            .map(|record| {
+                ZoneRecord::from_record(record, zone).map_err(|err| match err {
+                    PorkbunnError::ValidationError(msg) => PorkbunnError::ValidationError(format!(
+                        "record {} of {}: {}",
+                        record.id, zone, msg
+                    )),
+                    err => err,
+                })
+            })
+            .collect()
+    }
+
+    async fn create_record(&self, zone: &str, record: &RecordUpdate) -> Result<u64, PorkbunnError> {
+        // `RecordUpdate` serializes to exactly the body `dns/create` expects
+        let url = format!("dns/create/{}", zone);
+        let response: ResponseCreateRecord = self.execute(&Call::new(url, record)).await?;
+        Ok(response.id)
+    }
+
+    async fn update_record(
+        &self,
+        zone: &str,
+        id: u64,
+        record: &RecordUpdate,
+    ) -> Result<(), PorkbunnError> {
+        self.edit_dns_record(zone, id, record).await.map(|_| ())
+    }
+
+    async fn delete_record(&self, zone: &str, id: u64) -> Result<(), PorkbunnError> {
+        self.delete_dns_record(zone, id).await.map(|_| ())
+    }
+}
+
+/// Default and minimum TTL of Porkbun records, used for records created without one.
+pub(crate) const DEFAULT_TTL: u32 = 600;
+
+#[derive(Debug, Default)]
+struct InMemoryState {
+    last_id: u64,
+    zones: BTreeMap<String, Vec<ZoneRecord>>,
+}
+
+/// A `DnsProvider` keeping its zones in memory, for tests.
+///
+/// Clones share their state, so a test can hand a clone to the code under test
+/// and inspect the zones afterwards.
+///
+/// ```rust
+/// use porkbun_rs::dns_record::{DnsRecord, RecordUpdate};
+/// use porkbun_rs::provider::{DnsProvider, InMemoryProvider};
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let provider = InMemoryProvider::new().with_zone("example.com");
+/// let update = RecordUpdate {
+///     name: "www".to_string(),
+///     record: DnsRecord::Cname("example.com".to_string()),
+///     ttl: None,
+///     notes: None,
+/// };
+/// let id = provider.create_record("example.com", &update).await.unwrap();
+///
+/// let records = provider.list_records("example.com").await.unwrap();
+/// assert_eq!(records[0].id, id);
+/// assert_eq!(records[0].ttl, 600);
+/// # });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryProvider {
+    state: Arc<Mutex<InMemoryState>>,
+}
+
+impl InMemoryProvider {
+    /// Creates a provider without any zones.
+    pub fn new() -> InMemoryProvider {
+        InMemoryProvider::default()
+    }
+
+    /// Adds an empty zone.
+    pub fn with_zone<S: Into<String>>(self, zone: S) -> InMemoryProvider {
+        self.add_zone(zone);
+        self
+    }
+
+    /// Adds an empty zone, keeping the records if it already exists.
+    pub fn add_zone<S: Into<String>>(&self, zone: S) {
+        self.lock().zones.entry(zone.into()).or_default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, InMemoryState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Runs `f` on the records of `zone`.
+    fn with_records<T>(
+        &self,
+        zone: &str,
+        f: impl FnOnce(&mut Vec<ZoneRecord>, &mut u64) -> Result<T, PorkbunnError>,
+    ) -> Result<T, PorkbunnError> {
+        let mut state = self.lock();
+        let InMemoryState { last_id, zones } = &mut *state;
+        let records = zones
+            .get_mut(zone)
+            .ok_or_else(|| PorkbunnError::ZoneNotFound(zone.to_string()))?;
+        f(records, last_id)
+    }
+
+    fn not_found(zone: &str, id: u64) -> PorkbunnError {
+        PorkbunnError::RecordNotFound {
+            domain: zone.to_string(),
+            id,
+        }
+    }
+}
+
+impl DnsProvider for InMemoryProvider {
+    type Error = PorkbunnError;
+
+    async fn list_zones(&self) -> Result<Vec<String>, PorkbunnError> {
+        Ok(self.lock().zones.keys().cloned().collect())
+    }
+
+    async fn list_records(&self, zone: &str) -> Result<Vec<ZoneRecord>, PorkbunnError> {
+        self.with_records(zone, |records, _| Ok(records.clone()))
+    }
+
+    async fn create_record(&self, zone: &str, record: &RecordUpdate) -> Result<u64, PorkbunnError> {
+        self.with_records(zone, |records, last_id| {
+            *last_id += 1;
+            records.push(ZoneRecord {
+                id: *last_id,
+                name: record.name.clone(),
+                record: record.record.clone(),
+                ttl: record.ttl.unwrap_or(DEFAULT_TTL),
+                notes: record.notes.clone(),
+            });
+            Ok(*last_id)
+        })
+    }
+
+    async fn update_record(
+        &self,
+        zone: &str,
+        id: u64,
+        record: &RecordUpdate,
+    ) -> Result<(), PorkbunnError> {
+        self.with_records(zone, |records, _| {
+            let existing = records
+                .iter_mut()
+                .find(|r| r.id == id)
+                .ok_or_else(|| InMemoryProvider::not_found(zone, id))?;
+            *existing = ZoneRecord {
+                id,
+                name: record.name.clone(),
+                record: record.record.clone(),
+                ttl: record.ttl.unwrap_or(DEFAULT_TTL),
+                notes: record.notes.clone(),
+            };
+            Ok(())
+        })
+    }
+
+    async fn delete_record(&self, zone: &str, id: u64) -> Result<(), PorkbunnError> {
+        self.with_records(zone, |records, _| {
+            let index = records
+                .iter()
+                .position(|r| r.id == id)
+                .ok_or_else(|| InMemoryProvider::not_found(zone, id))?;
+            records.remove(index);
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_the_zone_from_record_names() {
+        assert_eq!(
+            subdomain_of("www.example.com", "example.com").unwrap(),
+            "www"
+        );
+        assert_eq!(
+            subdomain_of("a.b.example.com", "example.com").unwrap(),
+            "a.b"
+        );
+        assert_eq!(subdomain_of("example.com", "example.com").unwrap(), "");
+        assert_eq!(
+            subdomain_of("www.example.com", "Example.COM").unwrap(),
+            "www"
+        );
+        assert_eq!(
+            subdomain_of("WWW.Example.com.", "example.com").unwrap(),
+            "WWW"
+        );
+    }
+
+    #[test]
+    fn rejects_names_outside_the_zone() {
+        for (name, zone) in [
+            ("www.myexample.com", "example.com"),
+            ("www.example.org", "example.com"),
+            ("com", "example.com"),
+            ("", "example.com"),
+        ] {
+            assert!(subdomain_of(name, zone).is_err(), "{} in {}", name, zone);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use crate::errors::PorkbunnError;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Groups of endpoints which Porkbun throttles separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointClass {
+    /// `domain/checkDomain`, which has a much stricter limit than the rest of the API.
+    CheckDomain,
+    /// Every other endpoint.
+    Default,
+}
+
+impl EndpointClass {
+    /// Returns the class of the endpoint at `path`, e.g. `domain/checkDomain/example.com`.
+    pub fn of(path: &str) -> EndpointClass {
+        if path.starts_with("domain/checkDomain/") {
+            EndpointClass::CheckDomain
+        } else {
+            EndpointClass::Default
+        }
+    }
+}
+
+/// Allows `requests` requests every `per`, with bursts of up to `requests`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, per: Duration) -> RateLimit {
+        RateLimit { requests, per }
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        self.requests as f64 / self.per.as_secs_f64()
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    updated: Instant,
+}
+
+/// A token-bucket rate limiter with one bucket per `EndpointClass`.
+///
+/// Clones share their buckets, so every clone of a `PorkbunnClient` collectively
+/// stays under the configured limits. Classes without a limit are not throttled.
+///
+/// ```rust, no_run
+/// use porkbun_rs::porkbunn_client::PorkbunnClient;
+/// use porkbun_rs::rate_limit::{EndpointClass, RateLimit};
+/// use std::time::Duration;
+///
+/// # fn run() -> Result<(), porkbun_rs::errors::PorkbunnError> {
+/// let client = PorkbunnClient::builder()
+///     .credentials("pk1_...", "sk1_...")
+///     .rate_limit(EndpointClass::CheckDomain, RateLimit::new(1, Duration::from_secs(10)))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    buckets: Arc<HashMap<EndpointClass, Mutex<Bucket>>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter enforcing `limits`, starting with full buckets.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the limiter, or a `PorkbunnError::ValidationError` if a
+    /// limit allows zero requests or has a zero period.
+    pub fn new<I>(limits: I) -> Result<RateLimiter, PorkbunnError>
+    where
+        I: IntoIterator<Item = (EndpointClass, RateLimit)>,
+    {
+        let now = Instant::now();
+        let buckets = limits
+            .into_iter()
+            .map(|(class, limit)| {
+                if limit.requests == 0 || limit.per.is_zero() {
+                    return Err(PorkbunnError::ValidationError(format!(
+                        "invalid rate limit for {:?}: {:?}",
+                        class, limit
+                    )));
+                }
+                let bucket = Bucket {
+                    limit,
+                    tokens: limit.requests as f64,
+                    updated: now,
+                };
+                Ok((class, Mutex::new(bucket)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(RateLimiter {
+            buckets: Arc::new(buckets),
+        })
+    }
+
+    /// Waits until a request of the given class may be sent.
+    ///
+    /// The token is reserved before waiting, so concurrent callers queue up
+    /// behind each other instead of all waking up at the same time.
+    pub async fn acquire(&self, class: EndpointClass) {
+        let Some(bucket) = self.buckets.get(&class) else {
+            return;
+        };
+        let wait = {
+            let mut bucket = bucket.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let rate = bucket.limit.tokens_per_second();
+            let refilled = bucket.tokens + (now - bucket.updated).as_secs_f64() * rate;
+            bucket.tokens = refilled.min(bucket.limit.requests as f64) - 1.0;
+            bucket.updated = now;
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / rate)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tracing::debug!("Rate limit for {:?} reached, waiting {:?}", class, wait);
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(limit: RateLimit) -> RateLimiter {
+        RateLimiter::new([(EndpointClass::CheckDomain, limit)]).unwrap()
+    }
+
+    /// Acquires a token and returns how long it took since `start`, in milliseconds.
+    async fn acquire_at(limiter: &RateLimiter, class: EndpointClass, start: Instant) -> u128 {
+        limiter.acquire(class).await;
+        start.elapsed().as_millis()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bursts_up_to_the_limit_then_refills() {
+        let limiter = limiter(RateLimit::new(2, Duration::from_secs(1)));
+        let start = Instant::now();
+        let class = EndpointClass::CheckDomain;
+        assert_eq!(acquire_at(&limiter, class, start).await, 0);
+        assert_eq!(acquire_at(&limiter, class, start).await, 0);
+        assert_eq!(acquire_at(&limiter, class, start).await, 500);
+
+        // a long pause refills the bucket, but not beyond its size
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        let start = Instant::now();
+        assert_eq!(acquire_at(&limiter, class, start).await, 0);
+        assert_eq!(acquire_at(&limiter, class, start).await, 0);
+        assert_eq!(acquire_at(&limiter, class, start).await, 500);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_callers_queue_up() {
+        let limiter = limiter(RateLimit::new(1, Duration::from_millis(200)));
+        let start = Instant::now();
+        let class = EndpointClass::CheckDomain;
+        let waits = tokio::join!(
+            acquire_at(&limiter, class, start),
+            acquire_at(&limiter, class, start),
+            acquire_at(&limiter, class, start),
+            acquire_at(&limiter, class, start),
+        );
+        assert_eq!(waits, (0, 200, 400, 600));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn clones_share_their_buckets() {
+        let limiter = limiter(RateLimit::new(1, Duration::from_secs(1)));
+        let clone = limiter.clone();
+        let start = Instant::now();
+        let class = EndpointClass::CheckDomain;
+        assert_eq!(acquire_at(&limiter, class, start).await, 0);
+        assert_eq!(acquire_at(&clone, class, start).await, 1000);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn classes_without_a_limit_are_not_throttled() {
+        let limiter = limiter(RateLimit::new(1, Duration::from_secs(1)));
+        let start = Instant::now();
+        for _ in 0..10 {
+            assert_eq!(acquire_at(&limiter, EndpointClass::Default, start).await, 0);
+        }
+    }
+
+    #[test]
+    fn rejects_empty_limits() {
+        for limit in [
+            RateLimit::new(0, Duration::from_secs(1)),
+            RateLimit::new(1, Duration::ZERO),
+        ] {
+            let err = RateLimiter::new([(EndpointClass::Default, limit)]).unwrap_err();
+            assert!(
+                matches!(err, PorkbunnError::ValidationError(_)),
+                "{:?}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn classifies_endpoints() {
+        assert_eq!(
+            EndpointClass::of("domain/checkDomain/example.com"),
+            EndpointClass::CheckDomain
+        );
+        assert_eq!(
+            EndpointClass::of("dns/retrieve/example.com"),
+            EndpointClass::Default
+        );
+    }
+}
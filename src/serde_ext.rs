@@ -20,7 +20,12 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use serde::Serialize;
+use serde::de::{IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::marker::PhantomData;
+use std::str::FromStr;
 
 /// SerdeExt trait to convert any Serializable struct to
 /// json string.
@@ -30,7 +35,7 @@ pub trait SerdeExt {
     ///
     /// ```rust, no_run
     /// use serde::Serialize;
-    /// use schema_registry_client::prelude::*;
+    /// use porkbun_rs::serde_ext::SerdeExt;
     ///
     /// #[derive(Serialize)]
     /// pub struct Test{
@@ -39,7 +44,7 @@ pub trait SerdeExt {
     ///
     /// let test = Test{name:"degauss".to_string()};
     /// println!("{}", test.pretty_string());
-    ///
+    /// ```
     fn pretty_string(&self) -> String;
 
     fn pretty_print(&self);
@@ -57,3 +62,158 @@ where
         println!("{}", self.pretty_string());
     }
 }
+
+/// Deserializes a number which the API sends either as a JSON number or as a
+/// string, e.g. prices like `"9.68"` or key tags like `"64087"`.
+pub fn from_str_or_number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StrOrNumber<T> {
+        Str(String),
+        Number(T),
+    }
+
+    match StrOrNumber::<T>::deserialize(deserializer)? {
+        StrOrNumber::Str(s) => s.trim().parse().map_err(serde::de::Error::custom),
+        StrOrNumber::Number(n) => Ok(n),
+    }
+}
+
+/// Like `from_str_or_number`, for optional fields which the API sends as `null`,
+/// an empty string or a number, e.g. the `prio` of a DNS record.
+pub fn option_from_str_or_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + FromStr,
+    T::Err: Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StrOrNumber<T> {
+        Str(String),
+        Number(T),
+    }
+
+    match Option::<StrOrNumber<T>>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(StrOrNumber::Str(s)) if s.trim().is_empty() => Ok(None),
+        Some(StrOrNumber::Str(s)) => s.trim().parse().map(Some).map_err(serde::de::Error::custom),
+        Some(StrOrNumber::Number(n)) => Ok(Some(n)),
+    }
+}
+
+/// Deserializes a map which the API sends as an empty JSON array `[]` when it has
+/// no entries. Any other value, including a non-empty array, is an error.
+pub fn map_or_empty_seq<'de, D, V>(deserializer: D) -> Result<BTreeMap<String, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    struct MapOrEmptySeq<V>(PhantomData<V>);
+
+    impl<'de, V: Deserialize<'de>> Visitor<'de> for MapOrEmptySeq<V> {
+        type Value = BTreeMap<String, V>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a map or an empty array")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+            let mut map = BTreeMap::new();
+            while let Some((key, value)) = access.next_entry()? {
+                map.insert(key, value);
+            }
+            Ok(map)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+            if access.next_element::<IgnoredAny>()?.is_some() {
+                return Err(serde::de::Error::invalid_length(1, &"an empty array"));
+            }
+            Ok(BTreeMap::new())
+        }
+    }
+
+    deserializer.deserialize_any(MapOrEmptySeq(PhantomData))
+}
+
+/// (De)serializes a `bool` as the `"yes"`/`"no"` strings used by the API.
+pub mod yes_no {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(as_str(*value))
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        match value.to_lowercase().as_str() {
+            "yes" => Ok(true),
+            "no" => Ok(false),
+            other => Err(serde::de::Error::custom(format!(
+                "expected yes or no, got {}",
+                other
+            ))),
+        }
+    }
+
+    /// Returns the API representation of a `bool`.
+    pub fn as_str(value: bool) -> &'static str {
+        if value { "yes" } else { "no" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Records {
+        #[serde(deserialize_with = "map_or_empty_seq")]
+        records: BTreeMap<String, u16>,
+    }
+
+    fn parse(json: &str) -> Result<BTreeMap<String, u16>, serde_json::Error> {
+        serde_json::from_str::<Records>(json).map(|r| r.records)
+    }
+
+    #[test]
+    fn map_or_empty_seq_accepts_maps_and_empty_arrays() {
+        assert!(parse(r#"{"records":[]}"#).unwrap().is_empty());
+        let records = parse(r#"{"records":{"a":1,"b":2}}"#).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records["b"], 2);
+    }
+
+    #[test]
+    fn map_or_empty_seq_rejects_malformed_values() {
+        for json in [
+            r#"{"records":{"a":"x"}}"#,
+            r#"{"records":{"a":1,"b":{}}}"#,
+            r#"{"records":[1]}"#,
+            r#"{"records":"garbage"}"#,
+            r#"{"records":null}"#,
+        ] {
+            assert!(parse(json).is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn malformed_ds_records_are_not_dropped() {
+        let json =
+            r#"{"status":"SUCCESS","records":{"1":{"keyTag":"1","alg":"13","digestType":"2"}}}"#;
+        let err = serde_json::from_str::<crate::porkbunn_client::ResponseGetDsRecords>(json);
+        assert!(err.is_err());
+    }
+}
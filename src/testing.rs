@@ -0,0 +1,528 @@
+//! An in-process fake of the Porkbun v3 API for tests.
+//!
+//! `FakePorkbun` serves the endpoints for domains, nameservers, DNS records,
+//! pricing, availability checks and `ping` from in-memory state on a local port. Point a `PorkbunnClient` at
+//! `base_url()` with the `API_KEY` and `SECRET_KEY` of this module:
+//!
+//! ```rust
+//! use porkbun_rs::porkbunn_client::PorkbunnClient;
+//! use porkbun_rs::testing::{API_KEY, FakePorkbun, SECRET_KEY};
+//!
+//! # tokio::runtime::Runtime::new().unwrap().block_on(async {
+//! let fake = FakePorkbun::start().await.unwrap();
+//! fake.add_domain("example.com");
+//!
+//! let client = PorkbunnClient::new(&fake.base_url(), "v3", API_KEY, SECRET_KEY).unwrap();
+//! let records = client.list_dns_records("example.com").await.unwrap();
+//! assert!(records.records.is_empty());
+//! # });
+//! ```
+//!
+//! Failures can be injected with `fail_next` and throttling simulated with
+//! `simulate_rate_limit`. The same fake runs standalone with the `mock-server`
+//! subcommand.
+
+use crate::dns_record::{DnsRecord, RecordType};
+use crate::porkbunn_client::{Domain, Record};
+use crate::provider::{DEFAULT_TTL, fqdn};
+use crate::rate_limit::{EndpointClass, RateLimit};
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// API key accepted by the fake.
+pub const API_KEY: &str = "pk1_fake";
+
+/// Secret API key accepted by the fake.
+pub const SECRET_KEY: &str = "sk1_fake";
+
+/// Path the fake serves the API under, like `https://api.porkbun.com/api/json/`.
+const API_PATH: &str = "/api/json/";
+
+/// Number of domains `domain/listAll` returns per page.
+const PAGE_SIZE: usize = 1000;
+
+/// Registration, renewal and transfer prices served by `pricing/get` and
+/// `domain/checkDomain`, per TLD.
+const PRICING: &[(&str, &str, &str, &str)] = &[
+    ("com", "9.68", "10.81", "10.81"),
+    ("dev", "10.81", "12.87", "12.87"),
+    ("net", "11.48", "12.52", "12.52"),
+    ("org", "6.88", "10.74", "10.74"),
+];
+
+#[derive(Debug, Default)]
+struct ZoneState {
+    domain: Option<Domain>,
+    nameservers: Vec<String>,
+    records: Vec<Record>,
+}
+
+#[derive(Debug)]
+struct SimulatedLimit {
+    limit: RateLimit,
+    requests: VecDeque<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct FakeState {
+    last_id: u64,
+    zones: BTreeMap<String, ZoneState>,
+    failures: VecDeque<(u16, String)>,
+    limits: HashMap<EndpointClass, SimulatedLimit>,
+    page_size: Option<usize>,
+}
+
+/// A running fake of the Porkbun API, stopped when dropped.
+#[derive(Debug)]
+pub struct FakePorkbun {
+    addr: SocketAddr,
+    state: Arc<Mutex<FakeState>>,
+    server: JoinHandle<()>,
+}
+
+impl Drop for FakePorkbun {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+impl FakePorkbun {
+    /// Starts the fake on a free port of the loopback interface.
+    pub async fn start() -> std::io::Result<FakePorkbun> {
+        FakePorkbun::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await
+    }
+
+    /// Starts the fake on `addr`.
+    pub async fn bind(addr: SocketAddr) -> std::io::Result<FakePorkbun> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(FakeState::default()));
+        let server = tokio::spawn(serve(listener, state.clone()));
+        Ok(FakePorkbun {
+            addr,
+            state,
+            server,
+        })
+    }
+
+    /// Returns the address the fake listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the base URL to pass to `PorkbunnClient::new`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}{}", self.addr, API_PATH)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, FakeState> {
+        lock(&self.state)
+    }
+
+    /// Adds a domain to the account, without any DNS records.
+    pub fn add_domain(&self, domain: &str) {
+        let now = chrono::Utc::now().naive_utc();
+        let expires = now + chrono::Duration::days(365);
+        self.insert_domain(Domain {
+            auto_renew: "1".to_string(),
+            create_date: now.format("%Y-%m-%d %H:%M:%S").to_string(),
+            domain: domain.to_string(),
+            expire_date: expires.format("%Y-%m-%d %H:%M:%S").to_string(),
+            status: Some("ACTIVE".to_string()),
+            tld: domain.rsplit('.').next().unwrap_or_default().to_string(),
+            ..Domain::default()
+        });
+    }
+
+    /// Adds a domain to the account as given, e.g. with labels or an expiry
+    /// date, keeping its DNS records if it already exists.
+    pub fn insert_domain(&self, domain: Domain) {
+        let mut state = self.lock();
+        let zone = state.zones.entry(domain.domain.clone()).or_default();
+        zone.domain = Some(domain);
+        zone.nameservers = vec![
+            "curitiba.ns.porkbun.com".to_string(),
+            "fortaleza.ns.porkbun.com".to_string(),
+            "maceio.ns.porkbun.com".to_string(),
+            "salvador.ns.porkbun.com".to_string(),
+        ];
+    }
+
+    /// Returns the DNS records of a domain as the API would list them.
+    pub fn records(&self, domain: &str) -> Vec<Record> {
+        self.lock()
+            .zones
+            .get(domain)
+            .map(|zone| zone.records.clone())
+            .unwrap_or_default()
+    }
+
+    /// Sets how many domains `domain/listAll` returns per page, 1000 by default.
+    pub fn set_page_size(&self, page_size: usize) {
+        self.lock().page_size = Some(page_size);
+    }
+
+    /// Answers the next request with `http_status` and an `ERROR` body carrying
+    /// `message`, e.g. a 503 to exercise retries. Queued failures are used up in
+    /// order, one per request including retries.
+    pub fn fail_next(&self, http_status: u16, message: &str) {
+        self.lock()
+            .failures
+            .push_back((http_status, message.to_string()));
+    }
+
+    /// Rejects requests to endpoints of `class` with a 429 and a `Retry-After`
+    /// header once they exceed `limit` within a sliding window.
+    pub fn simulate_rate_limit(&self, class: EndpointClass, limit: RateLimit) {
+        self.lock().limits.insert(
+            class,
+            SimulatedLimit {
+                limit,
+                requests: VecDeque::new(),
+            },
+        );
+    }
+
+    /// Serves requests until the process receives Ctrl-C.
+    pub async fn run_until_ctrl_c(self) -> std::io::Result<()> {
+        tokio::signal::ctrl_c().await
+    }
+}
+
+fn lock(state: &Mutex<FakeState>) -> MutexGuard<'_, FakeState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<FakeState>>) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(err) => {
+                tracing::warn!("Failed to accept connection: {}", err);
+                continue;
+            }
+        };
+        let state = state.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| handle(state.clone(), peer.ip(), request));
+            if let Err(err) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("Connection from {} failed: {}", peer, err);
+            }
+        });
+    }
+}
+
+/// An answer of the fake: HTTP status, JSON body and optional `Retry-After` seconds.
+type Reply = (StatusCode, Value, Option<u64>);
+
+fn success(mut body: Value) -> Reply {
+    body["status"] = json!("SUCCESS");
+    (StatusCode::OK, body, None)
+}
+
+fn error(message: &str) -> Reply {
+    (
+        StatusCode::BAD_REQUEST,
+        json!({"status": "ERROR", "message": message}),
+        None,
+    )
+}
+
+async fn handle(
+    state: Arc<Mutex<FakeState>>,
+    peer: IpAddr,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    let path = request.uri().path().to_string();
+    let body = request.into_body().collect().await?.to_bytes();
+    let (status, body, retry_after) = match path.strip_prefix(API_PATH) {
+        Some(endpoint) => {
+            let endpoint = endpoint.split_once('/').map_or("", |(_version, rest)| rest);
+            match serde_json::from_slice::<Value>(&body) {
+                Ok(body) => dispatch(&mut lock(&state), peer, endpoint, &body),
+                Err(_) => error("Invalid JSON body."),
+            }
+        }
+        None => (
+            StatusCode::NOT_FOUND,
+            json!({"status": "ERROR", "message": "Not found."}),
+            None,
+        ),
+    };
+
+    let mut response = Response::builder()
+        .status(status)
+        .header("content-type", "application/json");
+    if let Some(seconds) = retry_after {
+        response = response.header("retry-after", seconds);
+    }
+    Ok(response
+        .body(Full::new(Bytes::from(body.to_string())))
+        .expect("valid response"))
+}
+
+fn dispatch(state: &mut FakeState, peer: IpAddr, endpoint: &str, body: &Value) -> Reply {
+    if let Some((status, message)) = state.failures.pop_front() {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        return (status, json!({"status": "ERROR", "message": message}), None);
+    }
+    if let Some(simulated) = state.limits.get_mut(&EndpointClass::of(endpoint)) {
+        let now = Instant::now();
+        while simulated
+            .requests
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= simulated.limit.per)
+        {
+            simulated.requests.pop_front();
+        }
+        if simulated.requests.len() >= simulated.limit.requests as usize {
+            let oldest = simulated.requests.front().copied().unwrap_or(now);
+            let wait = simulated
+                .limit
+                .per
+                .saturating_sub(now.duration_since(oldest));
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                json!({"status": "ERROR", "message": "Rate limit exceeded."}),
+                Some(wait.as_secs_f64().ceil() as u64),
+            );
+        }
+        simulated.requests.push_back(now);
+    }
+    if endpoint == "pricing/get" {
+        return pricing();
+    }
+    if body["apikey"] != API_KEY || body["secretapikey"] != SECRET_KEY {
+        return error("Invalid API key. (002)");
+    }
+
+    let parts: Vec<&str> = endpoint.split('/').collect();
+    match parts.as_slice() {
+        ["ping"] => success(json!({"yourIp": peer.to_string()})),
+        ["domain", "listAll"] => list_domains(state, body),
+        ["domain", "checkDomain", domain] => check_domain(state, domain),
+        [group, _, domain, ..] if *group == "dns" || *group == "domain" => {
+            let Some(zone) = state.zones.get_mut(*domain) else {
+                return error("Invalid domain.");
+            };
+            let last_id = &mut state.last_id;
+            match parts.as_slice() {
+                ["domain", "getNs", _] => success(json!({"ns": zone.nameservers})),
+                ["domain", "updateNs", _] => update_nameservers(zone, body),
+                ["dns", "create", _] => create_record(zone, last_id, domain, body),
+                ["dns", "edit", _, id] => edit_records(zone, domain, body, |r| r.id == *id),
+                ["dns", "delete", _, id] => delete_records(zone, |r| r.id == *id),
+                ["dns", "retrieve", _] => list_records(zone, |_| true),
+                ["dns", "retrieve", _, id] => list_records(zone, |r| r.id == *id),
+                ["dns", action, _, record_type, rest @ ..] if rest.len() <= 1 => {
+                    let name = fqdn(rest.first().copied().unwrap_or_default(), domain);
+                    let matches = |r: &Record| r.type_field == *record_type && r.name == name;
+                    match *action {
+                        "retrieveByNameType" => list_records(zone, matches),
+                        "editByNameType" => edit_records(zone, domain, body, matches),
+                        "deleteByNameType" => delete_records(zone, matches),
+                        _ => error("Invalid endpoint."),
+                    }
+                }
+                _ => error("Invalid endpoint."),
+            }
+        }
+        _ => error("Invalid endpoint."),
+    }
+}
+
+fn tld_pricing(tld: &str) -> Option<Value> {
+    pricing_table().remove(tld)
+}
+
+fn pricing_table() -> serde_json::Map<String, Value> {
+    PRICING
+        .iter()
+        .map(|(tld, registration, renewal, transfer)| {
+            let prices = json!({
+                "registration": registration,
+                "renewal": renewal,
+                "transfer": transfer,
+                "coupons": [],
+            });
+            (tld.to_string(), prices)
+        })
+        .collect()
+}
+
+fn pricing() -> Reply {
+    success(json!({ "pricing": pricing_table() }))
+}
+
+/// Domains of the account are taken, any other domain with a known TLD is available.
+fn check_domain(state: &FakeState, domain: &str) -> Reply {
+    let tld = domain.rsplit_once('.').map_or("", |(_, tld)| tld);
+    let Some(prices) = tld_pricing(tld) else {
+        return error("Invalid domain.");
+    };
+    // report the simulated limit, or a window which is never used up
+    let limits = match state.limits.get(&EndpointClass::CheckDomain) {
+        Some(simulated) => json!({
+            "TTL": simulated.limit.per.as_secs().to_string(),
+            "limit": simulated.limit.requests.to_string(),
+            "used": simulated.requests.len(),
+        }),
+        None => json!({"TTL": "10", "limit": "1000", "used": 0}),
+    };
+    success(json!({
+        "response": {
+            "avail": if state.zones.contains_key(domain) { "no" } else { "yes" },
+            "type": "registration",
+            "price": prices["registration"],
+            "firstYearPromo": "no",
+            "regularPrice": prices["registration"],
+            "premium": "no",
+            "additional": {
+                "renewal": {"type": "renewal", "price": prices["renewal"], "regularPrice": prices["renewal"]},
+                "transfer": {"type": "transfer", "price": prices["transfer"], "regularPrice": prices["transfer"]},
+            },
+        },
+        "limits": limits,
+    }))
+}
+
+fn list_domains(state: &FakeState, body: &Value) -> Reply {
+    let start = match &body["start"] {
+        Value::String(start) => start.parse().unwrap_or(0),
+        start => start.as_u64().unwrap_or(0) as usize,
+    };
+    let include_labels = body["includeLabels"] == "yes";
+    let domains: Vec<Domain> = state
+        .zones
+        .values()
+        .filter_map(|zone| zone.domain.clone())
+        .skip(start)
+        .take(state.page_size.unwrap_or(PAGE_SIZE))
+        .map(|domain| Domain {
+            labels: if include_labels {
+                domain.labels
+            } else {
+                Vec::new()
+            },
+            ..domain
+        })
+        .collect();
+    success(json!({ "domains": domains }))
+}
+
+fn update_nameservers(zone: &mut ZoneState, body: &Value) -> Reply {
+    let Some(ns) = body["ns"].as_array() else {
+        return error("Nameservers are required.");
+    };
+    zone.nameservers = ns
+        .iter()
+        .filter_map(|ns| ns.as_str().map(str::to_string))
+        .collect();
+    success(json!({}))
+}
+
+fn list_records(zone: &ZoneState, filter: impl Fn(&Record) -> bool) -> Reply {
+    let records: Vec<&Record> = zone.records.iter().filter(|r| filter(r)).collect();
+    success(json!({ "records": records }))
+}
+
+/// Validates the record fields of a create or edit body like Porkbun does.
+fn parse_record(body: &Value, record_type: &str) -> Result<(DnsRecord, u32), String> {
+    let record_type: RecordType = record_type
+        .parse()
+        .map_err(|_| "Invalid record type.".to_string())?;
+    let prio = match &body["prio"] {
+        Value::Null => None,
+        Value::String(prio) if prio.is_empty() => None,
+        Value::String(prio) => Some(prio.parse().map_err(|_| "Invalid priority.")?),
+        prio => Some(
+            prio.as_u64()
+                .and_then(|p| u16::try_from(p).ok())
+                .ok_or("Invalid priority.")?,
+        ),
+    };
+    let content = body["content"].as_str().unwrap_or_default();
+    let record = DnsRecord::parse(record_type, content, prio).map_err(|err| err.to_string())?;
+    let ttl = match &body["ttl"] {
+        Value::Null => DEFAULT_TTL,
+        Value::String(ttl) => ttl.parse().map_err(|_| "Invalid TTL.")?,
+        ttl => ttl.as_u64().ok_or("Invalid TTL.")? as u32,
+    };
+    Ok((record, ttl.max(DEFAULT_TTL)))
+}
+
+fn to_record(id: String, name: String, record: &DnsRecord, ttl: u32, body: &Value) -> Record {
+    Record {
+        id,
+        name,
+        type_field: record.type_name(),
+        content: record.content(),
+        ttl: ttl.to_string(),
+        prio: Some(record.prio().unwrap_or_default().to_string()),
+        notes: Some(body["notes"].as_str().unwrap_or_default().to_string()),
+    }
+}
+
+fn create_record(zone: &mut ZoneState, last_id: &mut u64, domain: &str, body: &Value) -> Reply {
+    let (record, ttl) = match parse_record(body, body["type"].as_str().unwrap_or_default()) {
+        Ok(parsed) => parsed,
+        Err(message) => return error(&message),
+    };
+    *last_id += 1;
+    let name = fqdn(body["name"].as_str().unwrap_or_default(), domain);
+    zone.records
+        .push(to_record(last_id.to_string(), name, &record, ttl, body));
+    success(json!({ "id": *last_id }))
+}
+
+fn edit_records(
+    zone: &mut ZoneState,
+    domain: &str,
+    body: &Value,
+    filter: impl Fn(&Record) -> bool,
+) -> Reply {
+    let mut edited = 0;
+    for existing in zone.records.iter_mut().filter(|r| filter(r)) {
+        let record_type = body["type"]
+            .as_str()
+            .unwrap_or(&existing.type_field)
+            .to_string();
+        let (record, ttl) = match parse_record(body, &record_type) {
+            Ok(parsed) => parsed,
+            Err(message) => return error(&message),
+        };
+        let name = match body["name"].as_str() {
+            Some(name) => fqdn(name, domain),
+            None => existing.name.clone(),
+        };
+        *existing = to_record(existing.id.clone(), name, &record, ttl, body);
+        edited += 1;
+    }
+    if edited == 0 {
+        return error("Record not found.");
+    }
+    success(json!({}))
+}
+
+fn delete_records(zone: &mut ZoneState, filter: impl Fn(&Record) -> bool) -> Reply {
+    let before = zone.records.len();
+    zone.records.retain(|r| !filter(r));
+    if zone.records.len() == before {
+        return error("Record not found.");
+    }
+    success(json!({}))
+}
@@ -0,0 +1,330 @@
+//! Drives `PorkbunnClient` against the fake API of the `testing` feature.
+
+use futures_util::TryStreamExt;
+use porkbun_rs::client::RetryPolicy;
+use porkbun_rs::dns_record::DnsRecord;
+use porkbun_rs::errors::PorkbunnError;
+use porkbun_rs::porkbunn_client::{Domain, Label, PorkbunnClient, RecordUpdate};
+use porkbun_rs::provider::DnsProvider;
+use porkbun_rs::rate_limit::{EndpointClass, RateLimit};
+use porkbun_rs::testing::{API_KEY, FakePorkbun, SECRET_KEY};
+use std::time::{Duration, Instant};
+
+async fn start() -> (FakePorkbun, PorkbunnClient) {
+    let fake = FakePorkbun::start().await.unwrap();
+    fake.add_domain("example.com");
+    let client = PorkbunnClient::builder()
+        .base_url(fake.base_url())
+        .credentials(API_KEY, SECRET_KEY)
+        .retry_policy(RetryPolicy {
+            initial_backoff: Duration::from_millis(10),
+            ..RetryPolicy::default()
+        })
+        .build()
+        .unwrap();
+    (fake, client)
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let (fake, client) = start().await;
+    fake.fail_next(503, "Service unavailable");
+
+    let response = client.ping().await.unwrap();
+    assert_eq!(response.your_ip, "127.0.0.1");
+}
+
+#[tokio::test]
+async fn server_errors_are_retryable() {
+    let (fake, client) = start().await;
+    for _ in 0..3 {
+        fake.fail_next(502, "");
+    }
+
+    let err = client.ping().await.unwrap_err();
+    assert!(
+        matches!(err, PorkbunnError::APIResponseError(_)),
+        "{:?}",
+        err
+    );
+    assert!(err.is_retryable());
+    assert_eq!(err.api_failure().unwrap().http_status, 502);
+}
+
+#[tokio::test]
+async fn dns_records_can_be_created_edited_and_deleted() {
+    let (fake, client) = start().await;
+
+    let mx = DnsRecord::Mx {
+        prio: 10,
+        host: "mail.example.com".to_string(),
+    };
+    let id = client
+        .create_dns_record("example.com", "mail", &mx, 600)
+        .await
+        .unwrap()
+        .id;
+    let record = client.get_dns_record("example.com", id).await.unwrap();
+    assert_eq!(record.name, "mail.example.com");
+    assert_eq!(DnsRecord::try_from(&record).unwrap(), mx);
+
+    let update = RecordUpdate {
+        name: "mail".to_string(),
+        record: DnsRecord::Mx {
+            prio: 20,
+            host: "mx.example.com".to_string(),
+        },
+        ttl: Some(900),
+        notes: Some("backup".to_string()),
+    };
+    client
+        .edit_dns_record("example.com", id, &update)
+        .await
+        .unwrap();
+    let record = client.get_dns_record("example.com", id).await.unwrap();
+    assert_eq!(record.content, "mx.example.com");
+    assert_eq!(record.prio.as_deref(), Some("20"));
+    assert_eq!(record.ttl, "900");
+    assert_eq!(record.notes.as_deref(), Some("backup"));
+
+    let by_name = client
+        .retrieve_dns_records_by_name_type("example.com", "MX", "mail")
+        .await
+        .unwrap();
+    assert_eq!(by_name.records, vec![record]);
+
+    client
+        .delete_dns_records_by_name_type("example.com", "MX", "mail")
+        .await
+        .unwrap();
+    assert!(fake.records("example.com").is_empty());
+    let err = client.get_dns_record("example.com", id).await.unwrap_err();
+    assert!(err.is_not_found(), "{:?}", err);
+}
+
+#[tokio::test]
+async fn the_client_is_a_dns_provider() {
+    let (_fake, client) = start().await;
+    assert_eq!(client.list_zones().await.unwrap(), vec!["example.com"]);
+
+    let update = RecordUpdate {
+        name: "_spf".to_string(),
+        record: DnsRecord::Txt(" v=spf1 -all ".to_string()),
+        ttl: None,
+        notes: None,
+    };
+    let id = client.create_record("example.com", &update).await.unwrap();
+    let records = DnsProvider::list_records(&client, "example.com")
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].id, id);
+    assert_eq!(records[0].name, "_spf");
+    assert_eq!(records[0].record, update.record);
+
+    client.delete_record("example.com", id).await.unwrap();
+    let records = DnsProvider::list_records(&client, "example.com")
+        .await
+        .unwrap();
+    assert!(records.is_empty());
+}
+
+#[tokio::test]
+async fn deletes_are_not_retried() {
+    let (fake, client) = start().await;
+    let id = client
+        .create_dns_record(
+            "example.com",
+            "www",
+            &DnsRecord::Cname("example.com".into()),
+            600,
+        )
+        .await
+        .unwrap()
+        .id;
+
+    // the first attempt might have deleted the record, a retry would then fail
+    fake.fail_next(503, "Service unavailable");
+    let err = client
+        .delete_dns_record("example.com", id)
+        .await
+        .unwrap_err();
+    assert!(err.is_retryable(), "{:?}", err);
+    assert_eq!(fake.records("example.com").len(), 1);
+
+    client.delete_dns_record("example.com", id).await.unwrap();
+    assert!(fake.records("example.com").is_empty());
+}
+
+#[tokio::test]
+async fn invalid_requests_are_rejected() {
+    let (fake, client) = start().await;
+
+    let err = client
+        .delete_dns_record("example.com", 42)
+        .await
+        .unwrap_err();
+    assert!(err.is_not_found(), "{:?}", err);
+
+    let err = client.list_dns_records("unknown.com").await.unwrap_err();
+    assert!(matches!(err, PorkbunnError::InvalidRequest(_)), "{:?}", err);
+
+    let client = PorkbunnClient::builder()
+        .base_url(fake.base_url())
+        .credentials(API_KEY, "sk1_wrong")
+        .build()
+        .unwrap();
+    let err = client.ping().await.unwrap_err();
+    assert!(err.is_auth(), "{:?}", err);
+    assert!(!err.is_retryable());
+}
+
+#[tokio::test]
+async fn rate_limited_requests_wait_for_retry_after() {
+    let (fake, client) = start().await;
+    fake.simulate_rate_limit(
+        EndpointClass::Default,
+        RateLimit::new(1, Duration::from_secs(1)),
+    );
+
+    client.ping().await.unwrap();
+    let started = Instant::now();
+    client.ping().await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn rate_limits_carry_retry_after() {
+    let (fake, _) = start().await;
+    fake.simulate_rate_limit(
+        EndpointClass::Default,
+        RateLimit::new(1, Duration::from_secs(1)),
+    );
+    let client = PorkbunnClient::builder()
+        .base_url(fake.base_url())
+        .credentials(API_KEY, SECRET_KEY)
+        .retry_policy(RetryPolicy::none())
+        .build()
+        .unwrap();
+
+    client.ping().await.unwrap();
+    let err = client.ping().await.unwrap_err();
+    assert!(matches!(err, PorkbunnError::RateLimited(_)), "{:?}", err);
+    assert_eq!(err.retry_after(), Some(Duration::from_secs(1)));
+    assert_eq!(err.api_failure().unwrap().http_status, 429);
+}
+
+#[tokio::test]
+async fn domain_listings_follow_pages() {
+    let (fake, client) = start().await;
+    fake.set_page_size(2);
+    for domain in ["example.net", "example.org", "example.dev", "example.io"] {
+        fake.add_domain(domain);
+    }
+    fake.insert_domain(Domain {
+        domain: "labelled.com".to_string(),
+        tld: "com".to_string(),
+        labels: vec![Label {
+            id: 7,
+            title: "clients".to_string(),
+            color: "#ff0000".to_string(),
+        }],
+        ..Domain::default()
+    });
+    let expected = vec![
+        "example.com",
+        "example.dev",
+        "example.io",
+        "example.net",
+        "example.org",
+        "labelled.com",
+    ];
+
+    let first = client.list_domains_page(0, false).await.unwrap();
+    assert_eq!(first.domains.len(), 2);
+
+    let all = client.list_domains(false).await.unwrap();
+    let names: Vec<&str> = all.domains.iter().map(|d| d.domain.as_str()).collect();
+    assert_eq!(names, expected);
+    assert!(all.domains.iter().all(|d| d.labels.is_empty()));
+
+    let streamed: Vec<Domain> = client.stream_domains(true).try_collect().await.unwrap();
+    let names: Vec<&str> = streamed.iter().map(|d| d.domain.as_str()).collect();
+    assert_eq!(names, expected);
+    assert_eq!(streamed[5].labels[0].title, "clients");
+    assert!(streamed[0].expires_at().is_some());
+}
+
+#[tokio::test]
+async fn pricing_needs_no_credentials() {
+    let (fake, _) = start().await;
+    let client = PorkbunnClient::builder()
+        .base_url(fake.base_url())
+        .build()
+        .unwrap();
+
+    let pricing = client.get_pricing().await.unwrap().pricing;
+    assert_eq!(pricing["com"].registration, 9.68);
+    assert!(pricing["com"].coupons.is_empty());
+}
+
+#[tokio::test]
+async fn domains_can_be_checked() {
+    let (fake, client) = start().await;
+
+    let response = client.check_domain("example.net").await.unwrap();
+    assert!(response.response.avail);
+    assert_eq!(response.response.price, 11.48);
+    assert_eq!(response.response.additional["renewal"].price, 12.52);
+    assert_eq!(response.limits.unwrap().wait(), Duration::ZERO);
+
+    let response = client.check_domain("example.com").await.unwrap();
+    assert!(!response.response.avail);
+
+    let err = client.check_domain("example.invalid").await.unwrap_err();
+    assert!(matches!(err, PorkbunnError::InvalidRequest(_)), "{:?}", err);
+
+    // the simulated limit shows up in the reported limits
+    fake.simulate_rate_limit(
+        EndpointClass::CheckDomain,
+        RateLimit::new(1, Duration::from_secs(10)),
+    );
+    let limits = client
+        .check_domain("example.org")
+        .await
+        .unwrap()
+        .limits
+        .unwrap();
+    assert_eq!((limits.ttl, limits.limit, limits.used), (10, 1, 1));
+    assert_eq!(limits.wait(), Duration::from_secs(10));
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn the_blocking_client_iterates_over_domains() {
+    // the fake needs a runtime of its own, the blocking client must not run inside one
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let fake = runtime.block_on(FakePorkbun::start()).unwrap();
+    fake.add_domain("example.com");
+    fake.add_domain("example.org");
+
+    let client = porkbun_rs::blocking::PorkbunnClient::builder()
+        .base_url(fake.base_url())
+        .credentials(API_KEY, SECRET_KEY)
+        .build()
+        .unwrap();
+    let domains: Vec<String> = client
+        .stream_domains(false)
+        .map(|domain| domain.unwrap().domain)
+        .collect();
+    assert_eq!(domains, vec!["example.com", "example.org"]);
+
+    let client = porkbun_rs::blocking::PorkbunnClient::builder()
+        .base_url(fake.base_url())
+        .credentials(API_KEY, "sk1_wrong")
+        .build()
+        .unwrap();
+    let mut domains = client.stream_domains(false);
+    assert!(domains.next().unwrap().unwrap_err().is_auth());
+    assert!(domains.next().is_none());
+}